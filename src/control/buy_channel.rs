@@ -2,27 +2,71 @@ pub mod buy_channel {
     use std::sync::{mpsc, MutexGuard};
     use std::time::{Duration, Instant};
 
-    use crate::file_control::read::read::get_buy_list_from_file;
+    use crate::control::checkpoint::checkpoint::{
+        load_checkpoint, save_pending_buys, set_cycle_stage, CYCLE_STAGE,
+    };
+    use crate::control::messages::messages::{
+        await_cycle_message, await_cycle_start, send_message, CYCLE_MESSAGE,
+    };
+    use crate::control::profiles::profiles::get_profile_strategy;
+    use crate::control::scheduler::scheduler::Pacer;
+    use crate::control::shutdown::shutdown::is_shutdown_requested;
+    use crate::control::supervisor::supervisor::heartbeat;
+    use crate::file_control::read::read::{
+        get_buy_list_from_file, get_exchanges_from_file, get_screening_results_from_file,
+    };
     use crate::file_control::write::write::write_buy_list_to_file;
     use crate::helpers::filters::filtering::stock_passes_filters;
+
+    use crate::helpers::exclusions::exclusions::get_exclusion_reason;
+    use crate::helpers::fx::fx::{load_fx_rates, FxRates};
     use crate::helpers::helpers::helpers::{get_full_company_info, sleep_thread};
+    use crate::helpers::listings::listings::get_listed_instrument;
+    use crate::helpers::money::money::Quantity;
+    use crate::helpers::screening::screening::ScreeningResult;
     use crate::helpers::types::types::FullCompanyInfo;
-    use crate::trading212::helpers::helpers::{
-        create_limit_order, get_buy_quantity, get_perc_increase, TradeDirection,
-    };
-    use crate::trading212::types::types::{BalanceObject, Instrument, LimitOrder};
-    use crate::{ChannelParam, MINIMUM_BUYS, SHOPPING_TIME};
+    use crate::helpers::watchlists::watchlists::is_filter_bypassed;
+    use crate::trading212::entry_pricer::entry_pricer::{print_fill_rates, EntryPricer};
+    use crate::trading212::helpers::helpers::get_buy_quantity;
+    use crate::trading212::schedules::schedules::ScheduleCalendar;
+    use crate::trading212::types::types::{BalanceObject, Instrument};
+    use crate::{ChannelParam, HANDSHAKE_TIMEOUT, ORDER_SPACING, SHOPPING_TIME};
+
+    fn is_already_bought(ticker: &String, data: &ChannelParam) -> bool {
+        let is_held: bool = data
+            .arc_positions_value
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|position| position.ticker.eq_ignore_ascii_case(ticker));
+
+        is_held
+            || data
+                .arc_order_manager
+                .lock()
+                .unwrap()
+                .has_open_order(ticker)
+    }
 
     pub fn start_buying(
-        ctrl_to_buy_rx: mpsc::Receiver<bool>,
-        sell_to_buy_rx: mpsc::Receiver<bool>,
-        buy_to_sell_tx: mpsc::Sender<bool>,
+        ctrl_to_buy_rx: &mpsc::Receiver<CYCLE_MESSAGE>,
+        sell_to_buy_rx: &mpsc::Receiver<CYCLE_MESSAGE>,
+        buy_to_sell_tx: &mpsc::Sender<CYCLE_MESSAGE>,
         data: ChannelParam,
     ) {
+        // Buys the last run had listed but not yet placed
+        let mut resumed_buys: Vec<String> = load_checkpoint()
+            .map(|checkpoint| checkpoint.pending_buys)
+            .unwrap_or_default();
+
         loop {
             // Wait for start signal from Control thread - Executes every 5 minutes or so
-            _ = ctrl_to_buy_rx.recv().unwrap();
-            println!("BT: Received start signal from Control.");
+            let cycle_id: u64 = match await_cycle_start(ctrl_to_buy_rx, "cycle start from Control")
+            {
+                Some(cycle_id) => cycle_id,
+                None => return,
+            };
+            println!("BT: Received start of cycle {} from Control.", cycle_id);
 
             // Populate shopping list
 
@@ -30,17 +74,66 @@ pub mod buy_channel {
             let buy_list_file_data_exists: bool = buy_list_file_data.as_ref().is_some();
             let mut buy_list: Vec<FullCompanyInfo> = Vec::new();
             let start: Instant = Instant::now();
+            let calendar: ScheduleCalendar =
+                ScheduleCalendar::new(&get_exchanges_from_file().unwrap_or_default());
+
+            // Resumed buys go first, with a fresh quote
+            for ticker in resumed_buys.drain(..) {
+                // The last run may have placed it just before stopping
+                if is_already_bought(&ticker, &data) {
+                    println!(
+                        "BT: {} already has an open order or position. Not resuming.",
+                        ticker
+                    );
+                    continue;
+                }
+                match get_listed_instrument(&ticker).and_then(get_full_company_info) {
+                    Some(company_info) => {
+                        println!("BT: Resuming pending buy of {}.", ticker);
+                        buy_list.push(company_info);
+                    }
+                    None => println!("BT: Unable to resume pending buy of {}.", ticker),
+                }
+            }
 
-            let instruments: MutexGuard<Vec<Instrument>> =
-                data.arc_instruments_value.lock().unwrap();
+            // Candidates ranked by the filter thread's screening job, best first
+            let candidates: Vec<ScreeningResult> = match get_screening_results_from_file() {
+                Some(candidates) => candidates,
+                None => {
+                    println!("BT: No screening results for today yet.");
+                    Vec::new()
+                }
+            };
 
-            for instrument in &*instruments {
+            for candidate in &candidates {
+                if is_shutdown_requested() || buy_list.len() >= get_profile_strategy().minimum_buys
+                {
+                    break;
+                }
+                heartbeat(&format!("screening {}", candidate.ticker));
+
+                let listed: Instrument = match get_listed_instrument(&candidate.ticker) {
+                    Some(listed) => listed,
+                    None => {
+                        println!("BT: {} is no longer listed. Skipping...", candidate.ticker);
+                        continue;
+                    }
+                };
+                let instrument: &Instrument = &listed;
                 if start.elapsed() >= Duration::from_secs(*SHOPPING_TIME) {
                     // If its been 5 minutes break
                     println!("BT: Buy cycle time limit elapsed.");
                     break;
                 }
 
+                if !calendar.is_empty() && !calendar.is_instrument_tradable_now(instrument) {
+                    println!(
+                        "BT: {} is outside trading hours. Skipping...",
+                        instrument.ticker
+                    );
+                    continue;
+                }
+
                 let raw_full_company_info: Option<FullCompanyInfo> =
                     get_full_company_info(instrument.clone());
 
@@ -53,7 +146,16 @@ pub mod buy_channel {
                     }
                 };
 
-                if !stock_passes_filters(&company_info) {
+                if let Some(reason) = get_exclusion_reason(
+                    instrument,
+                    Some(&company_info.company_info.finnhub_industry),
+                ) {
+                    println!("BT: Skipping {} - {}.\n", instrument.ticker, reason);
+                    continue;
+                }
+
+                // Screened earlier in the day - check again against the latest quote
+                if !is_filter_bypassed(&instrument.ticker) && !stock_passes_filters(&company_info) {
                     sleep_thread(5);
                     continue;
                 }
@@ -78,7 +180,7 @@ pub mod buy_channel {
                     buy_list.push(company_info);
                 }
 
-                if buy_list.len() == *MINIMUM_BUYS {
+                if buy_list.len() == get_profile_strategy().minimum_buys {
                     println!("BT: Buy list complete.");
                     break;
                 }
@@ -86,89 +188,120 @@ pub mod buy_channel {
                 println!("BT: Current buy list size: {}", buy_list.len());
             }
 
-            if buy_list.len() == 0 {
-                println!("BT: No buys executed this cycle.\nBT: Waiting for sell thread...");
+            if buy_list.len() > 0 {
+                // Update data on file
+                write_buy_list_to_file(&buy_list);
+            } else {
+                println!("BT: No buys executed this cycle.");
+            }
 
-                _ = sell_to_buy_rx.recv().unwrap();
-                println!("BT: Received signal from Sell thread.");
+            println!("BT: Waiting for sell thread...");
+            match await_cycle_message(
+                sell_to_buy_rx,
+                cycle_id,
+                "Sell thread",
+                Some(*HANDSHAKE_TIMEOUT),
+            ) {
+                Some(CYCLE_MESSAGE::SELLS_READY { .. }) => {}
+                Some(CYCLE_MESSAGE::ABORT { reason, .. }) => {
+                    println!("BT: Sell thread aborted cycle {} - {}.", cycle_id, reason);
+                    continue;
+                }
+                Some(message) => {
+                    println!(
+                        "BT: Unexpected {:?} from Sell thread. Skipping cycle.",
+                        message
+                    );
+                    continue;
+                }
+                None => {
+                    println!("BT: Sell thread never asked for buys. Skipping cycle.");
+                    continue;
+                }
+            }
 
+            if buy_list.len() == 0 {
                 // Send sell thread trigger
-                buy_to_sell_tx.send(true).unwrap();
-                return;
+                send_message(
+                    buy_to_sell_tx,
+                    CYCLE_MESSAGE::BUYS_COMPLETE {
+                        id: cycle_id,
+                        orders: Vec::new(),
+                    },
+                    "Sell thread",
+                );
+                continue;
             }
-
-            // Update data on file
-            write_buy_list_to_file(&buy_list);
-
-            _ = sell_to_buy_rx.recv().unwrap();
             println!("BT: Received signal from Sell thread. Executing buys...\n");
 
             // Execute buys
 
             let balance: MutexGuard<BalanceObject> = data.arc_balance_value.lock().unwrap();
 
-            let mut successful_buys: u32 = 0;
-            for company in &buy_list {
-                let buy_quantity: f64 = get_buy_quantity(&company, &balance);
+            // Without rates only instruments in the account currency can be sized
+            let fx_rates: FxRates = load_fx_rates().unwrap_or_default();
 
-                // Execute market order
-                let new_tick: String = company.instrument.ticker.clone();
-                println!("BT: Creating buy limit order for {}", new_tick);
+            let pricer: EntryPricer = EntryPricer::new();
+            let mut order_pacer: Pacer = Pacer::new("buy_orders", *ORDER_SPACING);
 
-                // buy .1% below current price - attempting to maximise margins for sale
-                let stock_price: f64 = company.company_stock_quote.current_price;
-                let buy_price: f64 = get_perc_increase(stock_price, 0.1);
+            set_cycle_stage(CYCLE_STAGE::BUYING);
 
-                let order_result: Option<LimitOrder> = create_limit_order(
-                    new_tick.clone(),
-                    buy_price,
-                    buy_quantity,
-                    TradeDirection::BUY,
-                );
+            // Whatever is left is saved so the next start can place it
+            let get_pending = |from: usize| -> Vec<String> {
+                buy_list[from..]
+                    .iter()
+                    .map(|company| company.instrument.ticker.clone())
+                    .collect()
+            };
+            save_pending_buys(get_pending(0));
 
-                match order_result {
-                    Some(_) => {
-                        println!("BT: Order creation successful.\n");
-                        successful_buys += 1;
-                        sleep_thread(5);
-                    }
-                    None => {
-                        println!("BT: Failed to create buy limit order. Retrying...\n");
-                        sleep_thread(5);
+            let mut placed_orders: Vec<u64> = Vec::new();
+            for (index, company) in buy_list.iter().enumerate() {
+                if is_shutdown_requested() {
+                    println!("BT: Shutdown requested. No further buys this cycle.");
+                    break;
+                }
+                heartbeat(&format!("buying {}", company.instrument.ticker));
 
-                        let retry: Option<LimitOrder> = create_limit_order(
-                            new_tick,
-                            buy_price + 0.01,
-                            buy_quantity,
-                            TradeDirection::BUY,
+                let buy_quantity: Quantity = match get_buy_quantity(&company, &balance, &fx_rates) {
+                    Some(buy_quantity) => buy_quantity,
+                    None => {
+                        println!(
+                            "BT: Unable to size {}. Skipping...\n",
+                            company.instrument.ticker
                         );
-
-                        match retry {
-                            Some(_) => {
-                                println!("BT: Retry order creation successful.\n");
-                                successful_buys += 1;
-                                sleep_thread(5);
-                            }
-                            None => {
-                                println!("BT: Failed second buy attempt. Skipping...\n");
-                                sleep_thread(5);
-                                continue;
-                            }
-                        }
+                        save_pending_buys(get_pending(index + 1));
                         continue;
                     }
+                };
+
+                if let Some(order_id) = pricer.enter(company, buy_quantity, &data.arc_order_manager)
+                {
+                    println!("BT: Order creation successful.\n");
+                    placed_orders.push(order_id);
                 }
+                save_pending_buys(get_pending(index + 1));
+                order_pacer.wait();
             }
+            set_cycle_stage(CYCLE_STAGE::SELLING);
 
             println!(
                 "\nBT: {}/{} buys complete.",
-                successful_buys,
+                placed_orders.len(),
                 buy_list.len()
             );
+            print_fill_rates();
 
             println!("BT: Triggering sell thread");
             // Send sell thread trigger
-            buy_to_sell_tx.send(true).unwrap();
+            send_message(
+                buy_to_sell_tx,
+                CYCLE_MESSAGE::BUYS_COMPLETE {
+                    id: cycle_id,
+                    orders: placed_orders,
+                },
+                "Sell thread",
+            );
         }
     }
 }
@@ -0,0 +1,98 @@
+pub mod checkpoint {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use lazy_static::lazy_static;
+    use serde::{Deserialize, Serialize};
+
+    use crate::control::profiles::profiles::{get_profile, get_profile_path};
+    use crate::helpers::helpers::helpers::{get_current_time, print_message, THREAD};
+    use crate::trading212::orders::orders::OrderManager;
+    use crate::trading212::types::types::SystemLimitOrder;
+
+    const CHECKPOINT_FILE: &str = "checkpoint.json";
+
+    lazy_static! {
+        // Control, buy and sell each write their own part of the checkpoint
+        static ref CHECKPOINT_LOCK: Mutex<()> = Mutex::new(());
+        // Stage of each profile's cycle, by profile name
+        static ref CURRENT_STAGES: Mutex<HashMap<String, CYCLE_STAGE>> = Mutex::new(HashMap::new());
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+    pub enum CYCLE_STAGE {
+        #[default]
+        IDLE,
+        REFRESHING, // Control is pulling balance, positions and orders
+        SELLING,    // Sell thread is placing or laddering sell orders
+        BUYING,     // Buy thread is placing buy orders
+    }
+
+    // Everything needed to pick up where the last run stopped
+    #[derive(Debug, Serialize, Deserialize, Default)]
+    pub struct Checkpoint {
+        pub saved_at: String,
+        pub cycle_stage: CYCLE_STAGE,
+        pub system_limit_orders: Vec<SystemLimitOrder>,
+        pub pending_buys: Vec<String>, // Buy list tickers not yet ordered
+        pub order_manager: OrderManager,
+    }
+
+    pub fn load_checkpoint() -> Option<Checkpoint> {
+        let contents: String = std::fs::read_to_string(get_profile_path(CHECKPOINT_FILE)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    fn update_checkpoint(update: impl FnOnce(&mut Checkpoint)) {
+        let _guard = CHECKPOINT_LOCK.lock().unwrap();
+        let mut checkpoint: Checkpoint = load_checkpoint().unwrap_or_default();
+        update(&mut checkpoint);
+        checkpoint.saved_at = get_current_time();
+
+        let contents: String =
+            serde_json::to_string_pretty(&checkpoint).expect("Checkpoint serialization failed");
+        if std::fs::write(get_profile_path(CHECKPOINT_FILE), contents).is_err() {
+            print_message(THREAD::FILE, "Failed to write checkpoint");
+        }
+    }
+
+    // True while any profile is part way through a cycle
+    pub fn is_cycle_active() -> bool {
+        CURRENT_STAGES
+            .lock()
+            .unwrap()
+            .values()
+            .any(|stage| *stage != CYCLE_STAGE::IDLE)
+    }
+
+    pub fn set_cycle_stage(stage: CYCLE_STAGE) {
+        CURRENT_STAGES
+            .lock()
+            .unwrap()
+            .insert(get_profile().name.to_string(), stage);
+        update_checkpoint(|checkpoint| checkpoint.cycle_stage = stage);
+    }
+
+    pub fn save_ladder_checkpoint(system_limit_orders: &Vec<SystemLimitOrder>) {
+        update_checkpoint(|checkpoint| {
+            checkpoint.system_limit_orders = system_limit_orders.clone()
+        });
+    }
+
+    pub fn save_pending_buys(tickers: Vec<String>) {
+        update_checkpoint(|checkpoint| checkpoint.pending_buys = tickers);
+    }
+
+    // Terminal orders are left out - only open orders need following after a restart
+    pub fn save_order_checkpoint(order_manager: &OrderManager) {
+        let open_orders: OrderManager = OrderManager {
+            orders: order_manager
+                .orders
+                .iter()
+                .filter(|(_, order)| !order.state.is_terminal())
+                .map(|(id, order)| (*id, order.clone()))
+                .collect(),
+        };
+        update_checkpoint(|checkpoint| checkpoint.order_manager = open_orders);
+    }
+}
@@ -3,70 +3,125 @@ pub mod control_channel {
     use std::thread;
     use std::time::Duration;
 
-    use crate::file_control::read::read::get_instruments_from_file;
+    use crate::control::checkpoint::checkpoint::{
+        save_order_checkpoint, set_cycle_stage, CYCLE_STAGE,
+    };
+    use crate::control::messages::messages::{await_cycle_message, send_message, CYCLE_MESSAGE};
+    use crate::control::reconciliation::reconciliation::reconcile;
+    use crate::control::supervisor::supervisor::{await_signal, heartbeat};
+    use crate::file_control::read::read::{get_exchanges_from_file, get_instruments_from_file};
     use crate::file_control::write::write::write_instruments_to_file;
     use crate::finnhub::utils::helpers::is_market_open;
     use crate::helpers::helpers::helpers::{shuffle_instruments, sleep_thread};
+    use crate::helpers::watchlists::watchlists::evaluate_watchlist_alerts;
     use crate::trading212::helpers::helpers::{
         get_account_balance, get_all_orders_request, get_all_positions, get_instruments,
     };
+    use crate::trading212::ledger::ledger::Ledger;
+    use crate::trading212::schedules::schedules::ScheduleCalendar;
     use crate::trading212::types::types::{BalanceObject, Instrument, LimitOrder, Position};
-    use crate::ChannelParam;
+    use crate::{ChannelParam, CYCLE_TIMEOUT};
+
+    fn is_trading_window_open() -> bool {
+        match get_exchanges_from_file() {
+            Some(exchanges) => {
+                let calendar: ScheduleCalendar = ScheduleCalendar::new(&exchanges);
+                if calendar.is_empty() {
+                    return is_market_open();
+                }
+                calendar.is_any_market_open()
+            }
+            // Fall back to Finnhub's US market status until schedules have been collected
+            None => is_market_open(),
+        }
+    }
 
     enum ReceiverType {
         BUY,
         SELL,
     }
 
-    fn notify_thread(sender: mpsc::Sender<bool>, receiver: ReceiverType) {
-        match sender.send(true) {
+    fn notify_thread(
+        sender: &mpsc::Sender<CYCLE_MESSAGE>,
+        message: CYCLE_MESSAGE,
+        receiver: ReceiverType,
+    ) {
+        match sender.send(message.clone()) {
             Ok(_) => match receiver {
-                ReceiverType::BUY => println!("CT: Signalled Buy thread."),
-                ReceiverType::SELL => println!("CT: Signalled Sell thread."),
+                ReceiverType::BUY => println!("CT: Sent {:?} to Buy thread.", message),
+                ReceiverType::SELL => println!("CT: Sent {:?} to Sell thread.", message),
             },
-            Err(e) => match receiver {
+            Err(_) => match receiver {
                 ReceiverType::BUY => println!("CT: Failed to signal Buy thread."),
                 ReceiverType::SELL => println!("CT: Failed to signal Sell thread."),
             },
         }
     }
 
+    fn notify_workers(
+        ctrl_to_buy_tx: &mpsc::Sender<CYCLE_MESSAGE>,
+        ctrl_to_sell_tx: &mpsc::Sender<CYCLE_MESSAGE>,
+        message: CYCLE_MESSAGE,
+    ) {
+        notify_thread(ctrl_to_buy_tx, message.clone(), ReceiverType::BUY);
+        notify_thread(ctrl_to_sell_tx, message, ReceiverType::SELL);
+    }
+
     pub fn start_control(
-        ctrl_to_sell_tx: mpsc::Sender<bool>,
-        ctrl_to_buy_tx: mpsc::Sender<bool>,
-        sell_to_ctrl_rx: mpsc::Receiver<bool>,
+        sched_to_ctrl_rx: &mpsc::Receiver<CYCLE_MESSAGE>,
+        ctrl_to_sched_tx: &mpsc::Sender<CYCLE_MESSAGE>,
+        ctrl_to_sell_tx: &mpsc::Sender<CYCLE_MESSAGE>,
+        ctrl_to_buy_tx: &mpsc::Sender<CYCLE_MESSAGE>,
+        sell_to_ctrl_rx: &mpsc::Receiver<CYCLE_MESSAGE>,
         data: ChannelParam,
     ) {
-        let mut should_get_new_instruments: bool = true;
-        let mut is_first_iteration: bool = true;
-
         // Data is pulled in loops so that if there is ever an error we can essentially retry until the data is susccessfully fetched
 
-        // Periodic data update - Every 5 mins
+        // Buy and Sell are told once when the market closes, not every cycle
+        let mut is_paused: bool = false;
+
+        // One cycle per control_refresh run - the scheduler decides when
         loop {
-            if !is_market_open() {
-                // Sleep 1 hour
+            let cycle_id: u64 =
+                match await_signal(sched_to_ctrl_rx, "cycle start from Scheduler", None) {
+                    Some(CYCLE_MESSAGE::CYCLE_START { id }) => id,
+                    Some(CYCLE_MESSAGE::SHUTDOWN) | None => {
+                        println!("CT: Shutting down. Stopping Buy and Sell threads.");
+                        notify_workers(ctrl_to_buy_tx, ctrl_to_sell_tx, CYCLE_MESSAGE::SHUTDOWN);
+                        return;
+                    }
+                    Some(message) => {
+                        println!("CT: Ignoring {:?} from Scheduler.", message);
+                        continue;
+                    }
+                };
+            println!("CT: Received start of cycle {} from Scheduler.", cycle_id);
+
+            if !is_trading_window_open() {
                 println!("\n--------------------- CT: MARKET CLOSED ---------------------\n");
-                sleep_thread(3600);
+                if !is_paused {
+                    notify_workers(
+                        ctrl_to_buy_tx,
+                        ctrl_to_sell_tx,
+                        CYCLE_MESSAGE::PAUSE {
+                            reason: String::from("market closed"),
+                        },
+                    );
+                    is_paused = true;
+                }
+                send_message(
+                    ctrl_to_sched_tx,
+                    CYCLE_MESSAGE::CYCLE_COMPLETE { id: cycle_id },
+                    "Scheduler",
+                );
                 continue;
             }
+            is_paused = false;
 
-            if !is_first_iteration {
-                // Wait for trigger from sell thread
-                println!("CT: Data updated. Waiting for Sell trigger to continue.");
-
-                let res = sell_to_ctrl_rx.recv();
-                match res {
-                    Err(e) => {
-                        println!("\n\nCT: Sell receiver error: {:?}", e);
-                    }
-                    Ok(_) => println!("\nCT: No error in sell receiver"),
-                }
-
-                println!("CT: Received signal from Sell thread to update data.");
-            }
+            set_cycle_stage(CYCLE_STAGE::REFRESHING);
 
             println!("CT: Populating data...");
+            heartbeat("refreshing account data");
 
             // Updating balances data
             'balance: loop {
@@ -142,14 +197,65 @@ pub mod control_channel {
                 }
             }
 
+            // Check the broker agrees with what Apollo thinks it holds
+            {
+                println!("CT: Reconciling positions and orders...");
+                let mut positions = data.arc_positions_value.lock().unwrap();
+                let limits = data.arc_limits_value.lock().unwrap();
+                let mut order_manager = data.arc_order_manager.lock().unwrap();
+
+                let ledger: Ledger = reconcile(&positions, &limits, &mut order_manager);
+
+                // Ignored positions are hidden from the sell thread
+                positions.retain(|position| {
+                    ledger.is_managed(&position.ticker)
+                        || order_manager.has_open_order(&position.ticker)
+                });
+                println!("CT: Reconciliation complete.\n");
+            }
+
+            heartbeat("checking watchlist alerts");
+            println!("CT: Checking watchlist alerts...");
+            evaluate_watchlist_alerts();
+
             // Notify threads of new data availability
             println!("CT: Data Updated. Notifying threads...\n");
-            is_first_iteration = false;
 
-            notify_thread(ctrl_to_buy_tx.clone(), ReceiverType::BUY);
-            notify_thread(ctrl_to_sell_tx.clone(), ReceiverType::SELL);
+            set_cycle_stage(CYCLE_STAGE::SELLING);
+            notify_workers(
+                ctrl_to_buy_tx,
+                ctrl_to_sell_tx,
+                CYCLE_MESSAGE::CYCLE_START { id: cycle_id },
+            );
+
+            // The cycle is over once the sell ladder finishes
+            println!("CT: Waiting for Sell thread to finish cycle {}.", cycle_id);
+            match await_cycle_message(
+                sell_to_ctrl_rx,
+                cycle_id,
+                "Sell thread",
+                Some(*CYCLE_TIMEOUT),
+            ) {
+                Some(CYCLE_MESSAGE::CYCLE_COMPLETE { .. }) => {
+                    println!("CT: Cycle {} complete.", cycle_id)
+                }
+                Some(CYCLE_MESSAGE::ABORT { reason, .. }) => {
+                    println!("CT: Sell thread aborted cycle {} - {}.", cycle_id, reason)
+                }
+                Some(message) => println!("CT: Unexpected {:?} from Sell thread.", message),
+                None => println!(
+                    "\nCT: Sell thread did not finish cycle {}. Abandoning it.",
+                    cycle_id
+                ),
+            }
 
-            // sleep_thread(600); // 10 Minutes
+            save_order_checkpoint(&data.arc_order_manager.lock().unwrap());
+            set_cycle_stage(CYCLE_STAGE::IDLE);
+            send_message(
+                ctrl_to_sched_tx,
+                CYCLE_MESSAGE::CYCLE_COMPLETE { id: cycle_id },
+                "Scheduler",
+            );
         }
     }
 }
@@ -0,0 +1,89 @@
+pub mod messages {
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Instant;
+
+    use crate::control::supervisor::supervisor::await_signal;
+
+    // Everything the scheduler, control, buy and sell threads say to each other.
+    // Cycle messages carry the id of the cycle they belong to so late replies can be told apart
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum CYCLE_MESSAGE {
+        CYCLE_START { id: u64 }, // Scheduler -> Control -> Buy and Sell
+        SELLS_READY { id: u64 }, // Sell -> Buy: initial sell orders are done, buy now
+        BUYS_COMPLETE { id: u64, orders: Vec<u64> }, // Buy -> Sell: ids of the buy orders placed, empty if none
+        CYCLE_COMPLETE { id: u64 },                  // Sell -> Control -> Scheduler
+        ABORT { id: u64, reason: String }, // Give up on this cycle - the sender is not going to carry on
+        PAUSE { reason: String },          // No cycles for now - wait for the next CYCLE_START
+        SHUTDOWN,                          // Return so the process can exit
+    }
+
+    impl CYCLE_MESSAGE {
+        pub fn get_cycle_id(&self) -> Option<u64> {
+            match self {
+                CYCLE_MESSAGE::CYCLE_START { id }
+                | CYCLE_MESSAGE::SELLS_READY { id }
+                | CYCLE_MESSAGE::BUYS_COMPLETE { id, .. }
+                | CYCLE_MESSAGE::CYCLE_COMPLETE { id }
+                | CYCLE_MESSAGE::ABORT { id, .. } => Some(*id),
+                CYCLE_MESSAGE::PAUSE { .. } | CYCLE_MESSAGE::SHUTDOWN => None,
+            }
+        }
+    }
+
+    pub fn send_message(sender: &mpsc::Sender<CYCLE_MESSAGE>, message: CYCLE_MESSAGE, to: &str) {
+        if sender.send(message.clone()).is_err() {
+            println!("MSG: {} is not listening for {:?}.", to, message);
+        }
+    }
+
+    // Waits for Control to start a cycle and returns its id.
+    // Pauses are logged and waited through. None once told to shut down or the channel closes
+    pub fn await_cycle_start(
+        receiver: &mpsc::Receiver<CYCLE_MESSAGE>,
+        waiting_for: &str,
+    ) -> Option<u64> {
+        let name: String = thread::current().name().unwrap_or("Worker").to_string();
+
+        loop {
+            match await_signal(receiver, waiting_for, None)? {
+                CYCLE_MESSAGE::CYCLE_START { id } => return Some(id),
+                CYCLE_MESSAGE::SHUTDOWN => {
+                    println!("MSG: {} received shutdown.", name);
+                    return None;
+                }
+                CYCLE_MESSAGE::PAUSE { reason } => {
+                    println!("MSG: {} paused - {}.", name, reason);
+                }
+                message => println!("MSG: {} ignoring {:?}.", name, message),
+            }
+        }
+    }
+
+    // Waits for the next message belonging to cycle_id, or one with no cycle at all.
+    // Messages left over from earlier cycles are dropped. None on timeout or a closed channel
+    pub fn await_cycle_message(
+        receiver: &mpsc::Receiver<CYCLE_MESSAGE>,
+        cycle_id: u64,
+        waiting_for: &str,
+        timeout: Option<u64>,
+    ) -> Option<CYCLE_MESSAGE> {
+        let start: Instant = Instant::now();
+
+        loop {
+            let remaining: Option<u64> =
+                timeout.map(|timeout| timeout.saturating_sub(start.elapsed().as_secs()));
+            let message: CYCLE_MESSAGE = await_signal(receiver, waiting_for, remaining)?;
+
+            match message.get_cycle_id() {
+                Some(id) if id != cycle_id => {
+                    println!(
+                        "MSG: Dropping {:?} from cycle {} while waiting on cycle {}.",
+                        message, id, cycle_id
+                    );
+                }
+                _ => return Some(message),
+            }
+        }
+    }
+}
@@ -1,3 +1,10 @@
 pub mod buy_channel;
+pub mod checkpoint;
 pub mod control_channel;
+pub mod messages;
+pub mod profiles;
+pub mod reconciliation;
+pub mod scheduler;
 pub mod sell_channel;
+pub mod shutdown;
+pub mod supervisor;
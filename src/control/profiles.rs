@@ -0,0 +1,103 @@
+pub mod profiles {
+    use std::cell::Cell;
+    use std::collections::HashSet;
+    use std::env;
+
+    use crate::{
+        AccountProfile, StrategyConfig, AMOUNT_OF_TICKERS_TO_BUY, EARNINGS_EXIT, ENTRY_PRICING,
+        MINIMUM_BUYS, POSITION_SIZING, PROFILES, SELL_PERCENT_DOWN, SELL_PERCENT_UP, SPEND_PERC,
+        USE_BRACKETS, VERSION,
+    };
+
+    thread_local! {
+        // Profile the current worker trades for. Threads that never set one use the first profile
+        static CURRENT_PROFILE: Cell<Option<&'static AccountProfile>> = Cell::new(None);
+    }
+
+    // Profiles that do not override a setting use the global Buy, Sizing and Sell Config
+    impl Default for StrategyConfig {
+        fn default() -> StrategyConfig {
+            StrategyConfig {
+                entry_pricing: &*ENTRY_PRICING,
+                position_sizing: &*POSITION_SIZING,
+                tickers_to_buy: *AMOUNT_OF_TICKERS_TO_BUY,
+                spend_perc: *SPEND_PERC,
+                minimum_buys: *MINIMUM_BUYS,
+                sell_percent_down: *SELL_PERCENT_DOWN,
+                sell_percent_up: *SELL_PERCENT_UP,
+                use_brackets: *USE_BRACKETS,
+                earnings_exit: &*EARNINGS_EXIT,
+            }
+        }
+    }
+
+    impl AccountProfile {
+        pub fn get_base_url(&self) -> String {
+            match self.base_url {
+                Some(base_url) => base_url.trim_end_matches('/').to_string(),
+                None => match self.version {
+                    VERSION::DEMO => String::from("https://demo.trading212.com"),
+                    VERSION::LIVE => String::from("https://live.trading212.com"),
+                },
+            }
+        }
+
+        // path is everything after /api/v0/ - "equity/orders/limit", "equity/portfolio", ...
+        pub fn get_api_url(&self, path: &str) -> String {
+            format!("{}/api/v0/{}", self.get_base_url(), path)
+        }
+
+        pub fn get_api_key(&self) -> String {
+            env::var(self.api_key_var).unwrap_or_else(|_| {
+                panic!("{} must be set for profile {}", self.api_key_var, self.name)
+            })
+        }
+
+        pub fn get_data_path(&self, file: &str) -> String {
+            std::fs::create_dir_all(self.data_dir)
+                .expect("Failed to create profile data directory.");
+            format!("{}/{}", self.data_dir, file)
+        }
+    }
+
+    pub fn set_profile(profile: &'static AccountProfile) {
+        CURRENT_PROFILE.with(|current| current.set(Some(profile)));
+    }
+
+    pub fn get_profile() -> &'static AccountProfile {
+        CURRENT_PROFILE
+            .with(|current| current.get())
+            .unwrap_or(&PROFILES[0])
+    }
+
+    // Journals, checkpoints and order state live in the current profile's data directory
+    pub fn get_profile_path(file: &str) -> String {
+        get_profile().get_data_path(file)
+    }
+
+    pub fn get_profile_strategy() -> &'static StrategyConfig {
+        &get_profile().strategy
+    }
+
+    // Two profiles writing the same journals or reporting under the same name would be mixed up
+    pub fn validate_profiles() -> Result<(), String> {
+        if PROFILES.is_empty() {
+            return Err(String::from("No account profiles configured"));
+        }
+
+        let mut names: HashSet<&str> = HashSet::new();
+        let mut data_dirs: HashSet<&str> = HashSet::new();
+        for profile in PROFILES.iter() {
+            if !names.insert(profile.name) {
+                return Err(format!("Profile name {} is used twice", profile.name));
+            }
+            if !data_dirs.insert(profile.data_dir.trim_end_matches('/')) {
+                return Err(format!(
+                    "Profile {} shares data directory {} with another profile",
+                    profile.name, profile.data_dir
+                ));
+            }
+        }
+        Ok(())
+    }
+}
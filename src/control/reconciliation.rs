@@ -0,0 +1,206 @@
+pub mod reconciliation {
+    use std::collections::HashSet;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::file_control::write::write::log_discrepancies;
+    use crate::helpers::helpers::helpers::get_current_time;
+    use crate::helpers::money::money::Quantity;
+    use crate::trading212::ledger::ledger::{load_ledger, save_ledger, Ledger};
+    use crate::trading212::orders::orders::OrderManager;
+    use crate::trading212::registry::registry::is_protected_ticker;
+    use crate::trading212::types::types::{LimitOrder, Position};
+    use crate::{RECONCILE_MODE, RECONCILIATION_POLICY};
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub enum DISCREPANCY_KIND {
+        UNKNOWN_POSITION,  // Held at the broker but never bought by Apollo
+        MISSING_POSITION,  // In the ledger but no longer held - sold outside Apollo
+        QUANTITY_MISMATCH, // Held by both but the share counts differ
+        UNKNOWN_ORDER,     // Open at the broker but not placed by Apollo
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct Discrepancy {
+        pub time: String,
+        pub kind: DISCREPANCY_KIND,
+        pub ticker: String,
+        pub order_id: Option<u64>,
+        pub expected: Option<Quantity>,
+        pub actual: Option<Quantity>,
+        pub adopted: bool,
+    }
+
+    fn discrepancy(
+        kind: DISCREPANCY_KIND,
+        ticker: &String,
+        order_id: Option<u64>,
+        expected: Option<Quantity>,
+        actual: Option<Quantity>,
+    ) -> Discrepancy {
+        Discrepancy {
+            time: get_current_time(),
+            kind,
+            ticker: ticker.clone(),
+            order_id,
+            expected,
+            actual,
+            adopted: match *RECONCILIATION_POLICY {
+                RECONCILE_MODE::ADOPT => true,
+                RECONCILE_MODE::IGNORE => false,
+            },
+        }
+    }
+
+    fn reconcile_positions(
+        ledger: &mut Ledger,
+        positions: &Vec<Position>,
+        order_manager: &OrderManager,
+    ) -> Vec<Discrepancy> {
+        let mut discrepancies: Vec<Discrepancy> = Vec::new();
+
+        for position in positions {
+            // Fills from Apollo's own open orders are recorded once the sell thread polls them
+            if order_manager.has_open_order(&position.ticker)
+                || is_protected_ticker(&position.ticker)
+            {
+                continue;
+            }
+
+            let actual: Quantity = Quantity::from_f64(position.quantity);
+
+            if !ledger.is_managed(&position.ticker) {
+                if ledger.ignored.contains(&position.ticker) {
+                    continue;
+                }
+                discrepancies.push(discrepancy(
+                    DISCREPANCY_KIND::UNKNOWN_POSITION,
+                    &position.ticker,
+                    None,
+                    None,
+                    Some(actual.clone()),
+                ));
+                match *RECONCILIATION_POLICY {
+                    RECONCILE_MODE::ADOPT => ledger.set_quantity(&position.ticker, actual),
+                    RECONCILE_MODE::IGNORE => {
+                        ledger.ignored.insert(position.ticker.clone());
+                    }
+                }
+                continue;
+            }
+
+            let expected: Quantity = ledger.get_quantity(&position.ticker);
+            if expected != actual {
+                discrepancies.push(discrepancy(
+                    DISCREPANCY_KIND::QUANTITY_MISMATCH,
+                    &position.ticker,
+                    None,
+                    Some(expected),
+                    Some(actual.clone()),
+                ));
+                if let RECONCILE_MODE::ADOPT = *RECONCILIATION_POLICY {
+                    ledger.set_quantity(&position.ticker, actual);
+                }
+            }
+        }
+
+        let held: HashSet<&String> = positions.iter().map(|position| &position.ticker).collect();
+        let missing: Vec<String> = ledger
+            .positions
+            .keys()
+            .filter(|ticker| !held.contains(ticker) && !order_manager.has_open_order(ticker))
+            .cloned()
+            .collect();
+
+        for ticker in missing {
+            discrepancies.push(discrepancy(
+                DISCREPANCY_KIND::MISSING_POSITION,
+                &ticker,
+                None,
+                Some(ledger.get_quantity(&ticker)),
+                None,
+            ));
+            // Nothing left to manage either way
+            ledger.set_quantity(&ticker, Quantity::default());
+        }
+
+        // An ignored position that has since closed can be forgotten
+        ledger.ignored.retain(|ticker| held.contains(ticker));
+
+        discrepancies
+    }
+
+    fn reconcile_orders(
+        order_manager: &mut OrderManager,
+        limit_orders: &Vec<LimitOrder>,
+        ledger: &Ledger,
+    ) -> Vec<Discrepancy> {
+        let mut discrepancies: Vec<Discrepancy> = Vec::new();
+
+        for order in limit_orders {
+            if order_manager.orders.contains_key(&order.id) {
+                continue;
+            }
+            // Orders on ignored or protected tickers are left to whoever placed them
+            if ledger.ignored.contains(&order.ticker) || is_protected_ticker(&order.ticker) {
+                continue;
+            }
+
+            discrepancies.push(discrepancy(
+                DISCREPANCY_KIND::UNKNOWN_ORDER,
+                &order.ticker,
+                Some(order.id),
+                None,
+                Some(order.quantity.clone()),
+            ));
+            if let RECONCILE_MODE::ADOPT = *RECONCILIATION_POLICY {
+                // Followed for fills only. Cancelling needs a registry entry, which only
+                // orders Apollo placed itself have, so hand placed orders are never clobbered
+                order_manager.track_limit_order(order);
+            }
+        }
+
+        discrepancies
+    }
+
+    // Compares the broker's positions and open orders against Apollo's ledger and order manager.
+    // Returns the ledger so the caller can drop positions Apollo does not manage
+    pub fn reconcile(
+        positions: &Vec<Position>,
+        limit_orders: &Vec<LimitOrder>,
+        order_manager: &mut OrderManager,
+    ) -> Ledger {
+        let mut ledger: Ledger = load_ledger();
+
+        let mut discrepancies: Vec<Discrepancy> =
+            reconcile_positions(&mut ledger, positions, order_manager);
+        discrepancies.append(&mut reconcile_orders(order_manager, limit_orders, &ledger));
+
+        for discrepancy in &discrepancies {
+            println!(
+                "CT: Reconciliation - {:?} {} (expected {:?}, actual {:?}) - {}",
+                discrepancy.kind,
+                discrepancy.ticker,
+                discrepancy
+                    .expected
+                    .as_ref()
+                    .map(|quantity| quantity.to_f64()),
+                discrepancy
+                    .actual
+                    .as_ref()
+                    .map(|quantity| quantity.to_f64()),
+                match discrepancy.adopted {
+                    true => "adopted",
+                    false => "ignored",
+                }
+            );
+        }
+
+        if !discrepancies.is_empty() {
+            log_discrepancies(&discrepancies);
+        }
+        save_ledger(&ledger);
+
+        ledger
+    }
+}
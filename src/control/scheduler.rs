@@ -0,0 +1,405 @@
+pub mod scheduler {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, JoinHandle};
+
+    use chrono::{DateTime, Days, NaiveTime, TimeZone, Utc};
+    use lazy_static::lazy_static;
+    use serde::{Deserialize, Serialize};
+
+    use crate::control::profiles::profiles::get_profile;
+    use crate::control::shutdown::shutdown::{is_shutdown_requested, should_stop_work};
+    use crate::control::supervisor::supervisor::heartbeat;
+    use crate::file_control::write::write::log_missed_runs;
+    use crate::helpers::helpers::helpers::{get_current_time, print_message, sleep_thread, THREAD};
+    use crate::SCHEDULE;
+
+    // Longest the scheduler sleeps before checking its jobs again
+    const MAX_SCHEDULER_SLEEP: i64 = 60;
+
+    lazy_static! {
+        // Every job and pacer, by name - what the logs and health checks report from
+        static ref JOB_STATUSES: Mutex<HashMap<String, JobStatus>> = Mutex::new(HashMap::new());
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+    pub struct JobStatus {
+        pub name: String,
+        pub running: bool,
+        pub last_started: Option<i64>,
+        pub last_finished: Option<i64>,
+        pub next_run: i64,
+        pub missed_runs: u32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct MissedRun {
+        pub time: String,
+        pub job: String,
+        pub scheduled_for: i64,
+        pub reason: String,
+    }
+
+    fn update_status(name: &str, update: impl FnOnce(&mut JobStatus)) {
+        let mut statuses = JOB_STATUSES.lock().unwrap();
+        let status: &mut JobStatus =
+            statuses
+                .entry(name.to_string())
+                .or_insert_with(|| JobStatus {
+                    name: name.to_string(),
+                    ..JobStatus::default()
+                });
+        update(status);
+    }
+
+    pub fn get_job_statuses() -> Vec<JobStatus> {
+        let mut statuses: Vec<JobStatus> = JOB_STATUSES.lock().unwrap().values().cloned().collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+
+    fn format_timestamp(timestamp: i64) -> String {
+        match Utc.timestamp_opt(timestamp, 0).single() {
+            Some(time) => time.format("%H:%M:%S").to_string(),
+            None => timestamp.to_string(),
+        }
+    }
+
+    fn record_missed_runs(name: &str, missed: Vec<MissedRun>) {
+        if missed.is_empty() {
+            return;
+        }
+        for missed_run in &missed {
+            println!(
+                "SCH: Missed {} run due at {} - {}.",
+                name,
+                format_timestamp(missed_run.scheduled_for),
+                missed_run.reason
+            );
+        }
+        update_status(name, |status| status.missed_runs += missed.len() as u32);
+        log_missed_runs(&missed);
+    }
+
+    fn missed_run(name: &str, scheduled_for: i64, reason: &str) -> MissedRun {
+        MissedRun {
+            time: get_current_time(),
+            job: name.to_string(),
+            scheduled_for,
+            reason: reason.to_string(),
+        }
+    }
+
+    // First run strictly after the given time
+    pub fn get_next_run(schedule: &SCHEDULE, after: i64) -> i64 {
+        match schedule {
+            SCHEDULE::INTERVAL(seconds) => after + (*seconds).max(1) as i64,
+            SCHEDULE::DAILY_AT(hour, minute) => {
+                let after_time: DateTime<Utc> = Utc.timestamp_opt(after, 0).single().unwrap();
+                let at: NaiveTime = NaiveTime::from_hms_opt(*hour, *minute, 0)
+                    .expect("DAILY_AT needs a valid hour and minute");
+
+                let today: i64 = after_time.date_naive().and_time(at).and_utc().timestamp();
+                match today > after {
+                    true => today,
+                    false => after_time
+                        .date_naive()
+                        .checked_add_days(Days::new(1))
+                        .unwrap()
+                        .and_time(at)
+                        .and_utc()
+                        .timestamp(),
+                }
+            }
+        }
+    }
+
+    // Clears the running flag however the task ends, panics included
+    struct RunningGuard {
+        name: String,
+        running: Arc<AtomicBool>,
+    }
+
+    impl Drop for RunningGuard {
+        fn drop(&mut self) {
+            self.running.store(false, Ordering::SeqCst);
+            update_status(&self.name, |status| {
+                status.running = false;
+                status.last_finished = Some(Utc::now().timestamp());
+            });
+        }
+    }
+
+    pub struct Job {
+        pub name: String,
+        pub schedule: &'static SCHEDULE,
+        pub next_run: i64,
+        running: Arc<AtomicBool>,
+        task: Arc<dyn Fn() + Send + Sync>,
+    }
+
+    impl Job {
+        // Runs the task on its own thread. Returns false if the last run has not finished
+        fn start(&self) -> bool {
+            if self.running.swap(true, Ordering::SeqCst) {
+                return false;
+            }
+            update_status(&self.name, |status| {
+                status.running = true;
+                status.last_started = Some(Utc::now().timestamp());
+            });
+
+            let guard: RunningGuard = RunningGuard {
+                name: self.name.clone(),
+                running: Arc::clone(&self.running),
+            };
+            let task: Arc<dyn Fn() + Send + Sync> = Arc::clone(&self.task);
+
+            let spawned = thread::Builder::new()
+                .name(format!("Job_{}", self.name))
+                .spawn(move || {
+                    let _guard: RunningGuard = guard;
+                    task();
+                });
+
+            if spawned.is_err() {
+                println!("SCH: Failed to spawn thread for {}.", self.name);
+            }
+            true
+        }
+    }
+
+    // Runs named jobs on interval or time of day schedules.
+    // A job never overlaps itself - a run that comes due while the last is going is logged as missed
+    pub struct Scheduler {
+        jobs: Vec<Job>,
+    }
+
+    impl Scheduler {
+        pub fn new() -> Scheduler {
+            Scheduler { jobs: Vec::new() }
+        }
+
+        // Interval jobs run straight away, time of day jobs wait for their first slot
+        pub fn add_job(
+            &mut self,
+            name: &str,
+            schedule: &'static SCHEDULE,
+            task: impl Fn() + Send + Sync + 'static,
+        ) {
+            let now: i64 = Utc::now().timestamp();
+            let next_run: i64 = match schedule {
+                SCHEDULE::INTERVAL(_) => now,
+                SCHEDULE::DAILY_AT(_, _) => get_next_run(schedule, now),
+            };
+
+            update_status(name, |status| status.next_run = next_run);
+            self.jobs.push(Job {
+                name: name.to_string(),
+                schedule,
+                next_run,
+                running: Arc::new(AtomicBool::new(false)),
+                task: Arc::new(task),
+            });
+        }
+
+        fn run_due_jobs(&mut self) {
+            let now: i64 = Utc::now().timestamp();
+
+            for job in self.jobs.iter_mut().filter(|job| job.next_run <= now) {
+                let mut missed: Vec<MissedRun> = Vec::new();
+
+                // Slots that passed entirely while the scheduler was not looking
+                let mut next_run: i64 = get_next_run(job.schedule, job.next_run);
+                while next_run <= now {
+                    missed.push(missed_run(&job.name, next_run, "scheduler was late"));
+                    next_run = get_next_run(job.schedule, next_run);
+                }
+
+                if is_shutdown_requested() {
+                    // Nothing new starts once Apollo is shutting down
+                    job.next_run = next_run;
+                    continue;
+                }
+
+                if job.start() {
+                    println!("SCH: Started {}.", job.name);
+                } else {
+                    missed.push(missed_run(
+                        &job.name,
+                        job.next_run,
+                        "previous run still in progress",
+                    ));
+                }
+
+                job.next_run = next_run;
+                update_status(&job.name, |status| status.next_run = next_run);
+                record_missed_runs(&job.name, missed);
+            }
+        }
+
+        pub fn start(mut self) -> JoinHandle<()> {
+            for job in &self.jobs {
+                println!(
+                    "SCH: {} scheduled {:?}. First run at {}.",
+                    job.name,
+                    job.schedule,
+                    format_timestamp(job.next_run)
+                );
+            }
+
+            thread::Builder::new()
+                .name("Scheduler".to_string())
+                .spawn(move || loop {
+                    self.run_due_jobs();
+
+                    let now: i64 = Utc::now().timestamp();
+                    let wake: i64 = self
+                        .jobs
+                        .iter()
+                        .map(|job| job.next_run)
+                        .min()
+                        .unwrap_or(now + MAX_SCHEDULER_SLEEP);
+                    sleep_thread((wake - now).clamp(1, MAX_SCHEDULER_SLEEP) as u64);
+                })
+                .expect("[Main Thread] Failed to spawn Scheduler thread")
+        }
+    }
+
+    // Fixed rate steps inside a running worker, such as sell ladder rungs or order spacing.
+    // Each wait ends one interval after the last, so slow work shortens the wait instead of stretching the cycle
+    pub struct Pacer {
+        name: String,
+        interval: i64,
+        next_run: i64,
+    }
+
+    impl Pacer {
+        // Pacers run inside a profile's workers, so each profile reports its own
+        pub fn new(name: &str, interval: u64) -> Pacer {
+            let name: String = format!("{}_{}", get_profile().name, name);
+            let interval: i64 = interval as i64;
+            let next_run: i64 = Utc::now().timestamp() + interval;
+            update_status(&name, |status| status.next_run = next_run);
+
+            Pacer {
+                name,
+                interval,
+                next_run,
+            }
+        }
+
+        pub fn wait(&mut self) {
+            heartbeat(&format!("waiting for {}", self.name));
+            let now: i64 = Utc::now().timestamp();
+
+            if now < self.next_run {
+                // Checked every second so a shutdown does not sit out a whole step
+                for _ in 0..(self.next_run - now) {
+                    if should_stop_work() {
+                        return;
+                    }
+                    sleep_thread(1);
+                }
+            } else if self.interval > 0 && now - self.next_run >= self.interval {
+                // The work between steps overran whole steps - those are gone, not queued up
+                let missed: Vec<MissedRun> = (1..=(now - self.next_run) / self.interval)
+                    .map(|step| {
+                        missed_run(
+                            &self.name,
+                            self.next_run + step * self.interval,
+                            "previous step overran",
+                        )
+                    })
+                    .collect();
+                record_missed_runs(&self.name, missed);
+            }
+
+            let started: i64 = Utc::now().timestamp();
+            self.next_run = started.max(self.next_run) + self.interval;
+
+            let next_run: i64 = self.next_run;
+            update_status(&self.name, |status| {
+                status.last_started = Some(started);
+                status.next_run = next_run;
+            });
+        }
+    }
+
+    pub fn print_job_statuses() {
+        let statuses: Vec<JobStatus> = get_job_statuses();
+        if statuses.is_empty() {
+            return;
+        }
+
+        print_message(THREAD::MAIN, "Job status");
+        for status in statuses {
+            println!(
+                "  {} - running: {}, last started: {}, next run: {}, missed: {}",
+                status.name,
+                status.running,
+                status
+                    .last_started
+                    .map(format_timestamp)
+                    .unwrap_or(String::from("never")),
+                format_timestamp(status.next_run),
+                status.missed_runs
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn timestamp(date: &str) -> i64 {
+            DateTime::parse_from_rfc3339(date).unwrap().timestamp()
+        }
+
+        #[test]
+        fn interval_runs_after_the_given_time() {
+            assert_eq!(get_next_run(&SCHEDULE::INTERVAL(300), 1_000), 1_300);
+            assert_eq!(get_next_run(&SCHEDULE::INTERVAL(0), 1_000), 1_001);
+        }
+
+        #[test]
+        fn daily_at_later_today_runs_today() {
+            let after: i64 = timestamp("2024-03-05T08:15:00Z");
+            assert_eq!(
+                get_next_run(&SCHEDULE::DAILY_AT(9, 30), after),
+                timestamp("2024-03-05T09:30:00Z")
+            );
+        }
+
+        #[test]
+        fn daily_at_already_passed_rolls_over_to_tomorrow() {
+            let after: i64 = timestamp("2024-03-05T10:00:00Z");
+            assert_eq!(
+                get_next_run(&SCHEDULE::DAILY_AT(9, 30), after),
+                timestamp("2024-03-06T09:30:00Z")
+            );
+        }
+
+        #[test]
+        fn daily_at_exactly_now_runs_tomorrow() {
+            let after: i64 = timestamp("2024-03-05T09:30:00Z");
+            assert_eq!(
+                get_next_run(&SCHEDULE::DAILY_AT(9, 30), after),
+                timestamp("2024-03-06T09:30:00Z")
+            );
+        }
+
+        #[test]
+        fn daily_at_rolls_over_month_and_year_ends() {
+            assert_eq!(
+                get_next_run(&SCHEDULE::DAILY_AT(0, 0), timestamp("2024-02-29T12:00:00Z")),
+                timestamp("2024-03-01T00:00:00Z")
+            );
+            assert_eq!(
+                get_next_run(&SCHEDULE::DAILY_AT(6, 0), timestamp("2024-12-31T23:59:59Z")),
+                timestamp("2025-01-01T06:00:00Z")
+            );
+        }
+    }
+}
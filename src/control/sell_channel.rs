@@ -1,18 +1,60 @@
 pub mod sell_channel {
+    use std::collections::HashSet;
     use std::sync::{mpsc, MutexGuard};
     use std::time::{Duration, SystemTime};
 
-    use crate::helpers::helpers::helpers::sleep_thread;
+    use chrono::Utc;
+
+    use crate::control::checkpoint::checkpoint::{load_checkpoint, save_ladder_checkpoint};
+    use crate::control::messages::messages::{
+        await_cycle_message, await_cycle_start, send_message, CYCLE_MESSAGE,
+    };
+    use crate::control::profiles::profiles::get_profile_strategy;
+    use crate::control::scheduler::scheduler::Pacer;
+    use crate::control::shutdown::shutdown::should_stop_work;
+    use crate::control::supervisor::supervisor::heartbeat;
+    use crate::file_control::types::types::SaleResult;
+    use crate::file_control::write::write::log_sale_result;
+    use crate::finnhub::symbols::symbols::lookup_fh_symbol;
+    use crate::helpers::exclusions::exclusions::get_ticker_exclusion_reason;
+    use crate::helpers::filters::filtering::is_within_earnings_blackout;
+    use crate::helpers::fx::fx::{get_expected_profit, load_fx_rates};
+    use crate::helpers::helpers::helpers::{
+        get_cached_earnings_calendar, get_current_time, sleep_thread,
+    };
+    use crate::helpers::listings::listings::get_listed_instrument;
+    use crate::helpers::money::money::{Price, Quantity};
+    use crate::trading212::brackets::brackets::{load_brackets, save_brackets, BracketBook};
     use crate::trading212::helpers::helpers::{
-        cancel_order, create_limit_order, get_all_orders_request, get_movement_direction,
-        get_perc_decrease, get_perc_increase, get_sell_quant, TradeDirection,
+        cancel_order, create_limit_order, create_market_order, get_all_orders_request,
+        get_movement_direction, get_perc_decrease, get_perc_increase, get_sell_quant,
+        TradeDirection,
+    };
+    use crate::trading212::orders::orders::{OrderEvent, ORDER_STATE};
+    use crate::trading212::registry::registry::{
+        is_protected_ticker, is_registered_order, prune_registry,
     };
     use crate::trading212::types::types::{
         HistoricalOrder, LimitOrder, Position, SystemLimitOrder, MOVEMENT_DIRECTION,
     };
-    use crate::{ChannelParam, LIMIT_WAIT_TIME};
+    use crate::{
+        ChannelParam, EARNINGS_EXIT_MODE, HANDSHAKE_TIMEOUT, LIMIT_WAIT_TIME, ORDER_SPACING,
+        SELL_LADDER_STEP_INTERVAL,
+    };
 
-    fn cancel_existing_sell_orders() {
+    // Starting the ladder here jumps straight to its tightest rungs
+    const TIGHTENED_SELL_ATTEMPTS: i32 = 6;
+
+    fn is_ticker_near_earnings(ticker: &String) -> bool {
+        let fh_ticker: String = lookup_fh_symbol(ticker);
+        match get_cached_earnings_calendar(&fh_ticker) {
+            Some(releases) => is_within_earnings_blackout(&releases),
+            None => false,
+        }
+    }
+
+    // Orders in protected_ids (live bracket legs) are left in place
+    fn cancel_existing_sell_orders(protected_ids: &Vec<u64>, order_pacer: &mut Pacer) {
         println!("ST: Cancelling all existing limit orders...");
         let orders_data: Option<Vec<LimitOrder>> = get_all_orders_request();
         let orders: Vec<LimitOrder> = match orders_data {
@@ -29,11 +71,20 @@ pub mod sell_channel {
         }
 
         for order in orders {
-            if order.quantity > 0.0 {
+            if order.quantity.is_buy() {
                 // Buy order, not a sell order. Not to be cancelled
                 continue;
             }
 
+            if protected_ids.contains(&order.id) {
+                continue;
+            }
+
+            // Only orders Apollo placed itself, and never on protected tickers
+            if !is_registered_order(order.id) || is_protected_ticker(&order.ticker) {
+                continue;
+            }
+
             println!("\nST: Cancelling {} order...", order.ticker);
             let res: Option<bool> = cancel_order(order.id);
             match res {
@@ -54,54 +105,292 @@ pub mod sell_channel {
                     }
                 }
             }
-            sleep_thread(10);
+            order_pacer.wait();
+        }
+    }
+
+    // Records each sale and drops ladders whose position has fully sold
+    fn apply_sell_events(
+        events: &Vec<OrderEvent>,
+        system_limit_orders: &mut Vec<SystemLimitOrder>,
+        bracket_book: &BracketBook,
+        positions: &Vec<Position>,
+    ) {
+        for event in events {
+            let is_final_fill: bool = match event.state {
+                ORDER_STATE::FILLED | ORDER_STATE::CANCELLED => true,
+                _ => false,
+            };
+            if !event.is_sell || !is_final_fill || event.filled_quantity.is_zero() {
+                continue;
+            }
+
+            let system_limit_order: Option<&mut SystemLimitOrder> = system_limit_orders
+                .iter_mut()
+                .find(|order| order.limit_order.ticker.eq_ignore_ascii_case(&event.ticker));
+
+            // A position that sold out between cycles is already gone from positions, so the
+            // ladder or bracket that sold it keeps its own copy of the average price
+            let average_price: Option<f64> = system_limit_order
+                .as_ref()
+                .map(|system_limit_order| system_limit_order.average_price)
+                .or_else(|| {
+                    bracket_book
+                        .get_bracket(&event.ticker)
+                        .map(|bracket| bracket.average_price)
+                })
+                .filter(|average_price| *average_price > 0.0)
+                .or_else(|| {
+                    positions
+                        .iter()
+                        .find(|position| position.ticker.eq_ignore_ascii_case(&event.ticker))
+                        .map(|position| position.average_price)
+                });
+
+            if let Some(system_limit_order) = system_limit_order {
+                system_limit_order.sold_quantity =
+                    Quantity(&system_limit_order.sold_quantity.0 + &event.filled_quantity.0);
+            }
+
+            if let (Some(average_price), Some(fill_price)) = (average_price, &event.fill_price) {
+                let quantity_sold: f64 = event.filled_quantity.to_f64();
+                println!(
+                    "ST: Sold {} {} at {}.",
+                    quantity_sold, event.ticker, fill_price
+                );
+                // Account currency profit after FX fees. Without an instrument or rates
+                // only the instrument currency difference is known
+                let profit: f64 = get_listed_instrument(&event.ticker)
+                    .zip(load_fx_rates())
+                    .and_then(|(instrument, rates)| {
+                        get_expected_profit(
+                            &Price::from_f64(average_price),
+                            fill_price,
+                            &event.filled_quantity,
+                            &instrument.currency_code,
+                            &rates,
+                        )
+                    })
+                    .map(|profit| profit.to_f64())
+                    .unwrap_or((fill_price.to_f64() - average_price) * quantity_sold);
+                log_sale_result(SaleResult {
+                    sale_time: get_current_time(),
+                    sale_price: fill_price.to_f64(),
+                    quantity_sold,
+                    ticker: event.ticker.clone(),
+                    profit,
+                });
+            }
         }
+
+        // Fully sold positions need no further rungs. A position that is gone has fully sold
+        system_limit_orders.retain(|system_limit_order| {
+            match positions.iter().find(|position| {
+                position
+                    .ticker
+                    .eq_ignore_ascii_case(&system_limit_order.limit_order.ticker)
+            }) {
+                Some(position) => system_limit_order.sold_quantity.to_f64() < position.quantity,
+                None => false,
+            }
+        });
     }
 
     pub fn start_selling(
-        ctrl_to_sell_rx: mpsc::Receiver<bool>,
-        sell_to_ctrl_tx: mpsc::Sender<bool>,
-        sell_to_buy_tx: mpsc::Sender<bool>,
-        buy_to_sell_rx: mpsc::Receiver<bool>,
+        ctrl_to_sell_rx: &mpsc::Receiver<CYCLE_MESSAGE>,
+        sell_to_ctrl_tx: &mpsc::Sender<CYCLE_MESSAGE>,
+        sell_to_buy_tx: &mpsc::Sender<CYCLE_MESSAGE>,
+        buy_to_sell_rx: &mpsc::Receiver<CYCLE_MESSAGE>,
         data: ChannelParam,
     ) {
         let mut system_limit_orders: Vec<SystemLimitOrder> = Vec::new();
+
+        // Ladders the last run was working - their attempts carry over
+        let mut resumed_ladders: Vec<SystemLimitOrder> = load_checkpoint()
+            .map(|checkpoint| checkpoint.system_limit_orders)
+            .unwrap_or_default();
         let mut sale_results: Vec<HistoricalOrder> = Vec::new(); // TODO
 
         loop {
             // Wait for start signal from Control thread
-            _ = ctrl_to_sell_rx.recv().unwrap();
-            println!("ST: Received start signal from Control.");
+            let cycle_id: u64 = match await_cycle_start(ctrl_to_sell_rx, "cycle start from Control")
+            {
+                Some(cycle_id) => cycle_id,
+                None => return,
+            };
+            println!("ST: Received start of cycle {} from Control.", cycle_id);
+
+            // Rungs are repriced on a fixed step and orders go out at a fixed spacing
+            let mut ladder_pacer: Pacer =
+                Pacer::new("sell_ladder_step", *SELL_LADDER_STEP_INTERVAL);
+            let mut order_pacer: Pacer = Pacer::new("sell_orders", *ORDER_SPACING);
+
+            // Brackets hold a take profit and a stop at the broker instead of the ladder below
+            let mut bracket_book: BracketBook = match get_profile_strategy().use_brackets {
+                true => load_brackets(),
+                false => BracketBook::default(),
+            };
+
+            if get_profile_strategy().use_brackets {
+                let positions: MutexGuard<Vec<Position>> = data.arc_positions_value.lock().unwrap();
+                let mut order_manager = data.arc_order_manager.lock().unwrap();
+
+                let events: Vec<OrderEvent> = order_manager.poll();
+                apply_sell_events(&events, &mut system_limit_orders, &bracket_book, &positions);
+                bracket_book.apply_events(&events);
+                bracket_book.sync_positions(&positions, &mut order_manager);
+                save_brackets(&bracket_book);
+            }
+
+            // Registry entries go once nothing - broker, order manager or bracket - reports them open
+            let listed_at: i64 = Utc::now().timestamp();
+            if let Some(active_orders) = get_all_orders_request() {
+                let mut open_ids: HashSet<u64> = bracket_book.get_order_ids().into_iter().collect();
+                open_ids.extend(active_orders.iter().map(|order| order.id));
+                open_ids.extend(
+                    data.arc_order_manager
+                        .lock()
+                        .unwrap()
+                        .get_open_orders()
+                        .iter()
+                        .map(|order| order.id),
+                );
+                prune_registry(&open_ids, listed_at);
+            }
+
+            cancel_existing_sell_orders(&bracket_book.get_order_ids(), &mut order_pacer);
 
-            sleep_thread(20);
-            cancel_existing_sell_orders();
+            // Earnings lookups can wait on Finnhub, so they run before the positions lock is held
+            let earnings_due: Vec<String> = match get_profile_strategy().earnings_exit {
+                EARNINGS_EXIT_MODE::OFF => Vec::new(),
+                _ => {
+                    let tickers: Vec<String> = data
+                        .arc_positions_value
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .map(|position| position.ticker.clone())
+                        .filter(|ticker| {
+                            !bracket_book.has_bracket(ticker) && !is_protected_ticker(ticker)
+                        })
+                        .collect();
+                    tickers
+                        .into_iter()
+                        .filter(|ticker| is_ticker_near_earnings(ticker))
+                        .collect()
+                }
+            };
 
             // Create initial sell limit order for each position
             let positions: MutexGuard<Vec<Position>> = data.arc_positions_value.lock().unwrap();
             'initial_limit_creation_loop: for position in &*positions {
+                if should_stop_work() {
+                    println!("ST: Shutdown requested. No further sell orders.");
+                    break 'initial_limit_creation_loop;
+                }
+
+                if bracket_book.has_bracket(&position.ticker)
+                    || is_protected_ticker(&position.ticker)
+                {
+                    continue 'initial_limit_creation_loop;
+                }
+                heartbeat(&format!("opening ladder for {}", position.ticker));
+
+                if let Some(reason) = get_ticker_exclusion_reason(&position.ticker) {
+                    println!("ST: Skipping {} - {}.", position.ticker, reason);
+                    continue 'initial_limit_creation_loop;
+                }
+
+                // Last cycle's ladder rungs were just cancelled, so only a pending market
+                // exit still counts as an open order here
+                if data
+                    .arc_order_manager
+                    .lock()
+                    .unwrap()
+                    .has_open_market_order(&position.ticker)
+                {
+                    println!("ST: Exit for {} still pending. Skipping.", position.ticker);
+                    continue 'initial_limit_creation_loop;
+                }
+
                 let movement_direction: MOVEMENT_DIRECTION = get_movement_direction(&position);
-                let sale_price: f64 = get_perc_increase(position.current_price, 1.0);
-                let sell_quant: f64 = get_sell_quant(&position);
+                let sale_price: Price = get_perc_increase(position.current_price, 1.0);
+                let sell_quant: Quantity = get_sell_quant(&position);
+
+                let near_earnings: bool = earnings_due.contains(&position.ticker);
+
+                if near_earnings {
+                    if let EARNINGS_EXIT_MODE::EXIT = get_profile_strategy().earnings_exit {
+                        println!(
+                            "ST: Earnings due for {}. Exiting position...",
+                            position.ticker
+                        );
+                        // Tracked like every other order so the fill reaches the ledger and sale log
+                        if let Some(order) =
+                            create_market_order(position.ticker.clone(), sell_quant.negate())
+                        {
+                            data.arc_order_manager
+                                .lock()
+                                .unwrap()
+                                .track_market_order(&order);
+                        }
+                        order_pacer.wait();
+                        continue 'initial_limit_creation_loop;
+                    }
+                    println!(
+                        "ST: Earnings due for {}. Tightening sell ladder.",
+                        position.ticker
+                    );
+                }
 
                 println!("ST: Creating limit order for {}", position.ticker);
 
                 match create_limit_order(
                     position.ticker.clone(),
                     sale_price,
-                    -sell_quant,
+                    sell_quant.negate(),
                     TradeDirection::SELL,
                 ) {
                     None => {
                         println!("ST: Failed to create initial sell order. Skipping...\n");
                     }
                     Some(limit_order) => {
+                        data.arc_order_manager
+                            .lock()
+                            .unwrap()
+                            .track_limit_order(&limit_order);
+
+                        let resumed: Option<&SystemLimitOrder> =
+                            resumed_ladders.iter().find(|ladder| {
+                                ladder
+                                    .limit_order
+                                    .ticker
+                                    .eq_ignore_ascii_case(&position.ticker)
+                            });
+
                         let system_limit_order: SystemLimitOrder = SystemLimitOrder {
                             creation_time: SystemTime::now(),
                             movement_direction,
-                            sell_attempts: 0,
+                            sell_attempts: match (near_earnings, resumed) {
+                                (true, _) => TIGHTENED_SELL_ATTEMPTS,
+                                (false, Some(resumed)) => resumed.sell_attempts,
+                                (false, None) => 0,
+                            },
                             limit_order,
+                            // position.quantity is fresh from the broker and already excludes
+                            // anything sold before the restart, so only the attempts carry over
+                            sold_quantity: Quantity::default(),
+                            average_price: position.average_price,
                         };
 
+                        // One ladder per position
+                        system_limit_orders.retain(|ladder| {
+                            !ladder
+                                .limit_order
+                                .ticker
+                                .eq_ignore_ascii_case(&position.ticker)
+                        });
+
                         println!(
                             "ST: Created system limit order for {}.\n",
                             &system_limit_order.limit_order.ticker
@@ -109,24 +398,63 @@ pub mod sell_channel {
                         system_limit_orders.push(system_limit_order);
                     }
                 }
-                sleep_thread(10);
+                order_pacer.wait();
             }
             drop(positions);
-
-            // Wait three minutes before cancelling and updating sell orders
-            sleep_thread(180);
-            cancel_existing_sell_orders();
-            println!("\nST: All existing orders cancelled.");
+            resumed_ladders.clear();
+            save_ladder_checkpoint(&system_limit_orders);
+
+            // Give the initial orders one ladder step before cancelling and updating them
+            ladder_pacer.wait();
+            if !should_stop_work() {
+                cancel_existing_sell_orders(&bracket_book.get_order_ids(), &mut order_pacer);
+                println!("\nST: All existing orders cancelled.");
+            }
 
             // wait for buys to complete
-            println!("ST: Sending trigger to buy thread.\n");
-            sell_to_buy_tx.send(true).unwrap();
-            _ = buy_to_sell_rx.recv().unwrap();
-            println!("ST: Received response from buy thread.\nST: Creating fresh sell orders.");
-            sleep_thread(180);
+            if should_stop_work() {
+                println!("ST: Shutdown requested. Telling buy thread not to buy.\n");
+                send_message(
+                    sell_to_buy_tx,
+                    CYCLE_MESSAGE::ABORT {
+                        id: cycle_id,
+                        reason: String::from("shutting down"),
+                    },
+                    "Buy thread",
+                );
+            } else {
+                println!("ST: Sending trigger to buy thread.\n");
+                send_message(
+                    sell_to_buy_tx,
+                    CYCLE_MESSAGE::SELLS_READY { id: cycle_id },
+                    "Buy thread",
+                );
+                match await_cycle_message(
+                    buy_to_sell_rx,
+                    cycle_id,
+                    "Buy thread",
+                    Some(*HANDSHAKE_TIMEOUT),
+                ) {
+                    Some(CYCLE_MESSAGE::BUYS_COMPLETE { orders, .. }) => {
+                        println!("ST: Buy thread placed {} orders.", orders.len())
+                    }
+                    Some(CYCLE_MESSAGE::ABORT { reason, .. }) => {
+                        println!("ST: Buy thread aborted - {}.", reason)
+                    }
+                    Some(message) => println!("ST: Unexpected {:?} from buy thread.", message),
+                    None => println!("ST: No response from buy thread. Continuing without it."),
+                }
+            }
+            println!("ST: Creating fresh sell orders.");
 
             // Check if limit orders have hit. Change asking amount - 6 checks
             'order_loop: for i in 0..6 {
+                ladder_pacer.wait();
+                if should_stop_work() {
+                    println!("ST: Shutdown requested. Leaving the sell ladder.");
+                    break 'order_loop;
+                }
+
                 // Create a new set of limit orders for each existing position
                 let positions: MutexGuard<Vec<Position>> = data.arc_positions_value.lock().unwrap();
 
@@ -139,17 +467,33 @@ pub mod sell_channel {
                                 "{}: Current Price: {} - Limit Price: {}",
                                 o.limit_order.ticker,
                                 position.current_price,
-                                o.limit_order.limit_price.unwrap()
+                                o.limit_order.limit_price.as_ref().unwrap()
                             );
                         }
                     }
                 }
                 println!(" ");
 
-                cancel_existing_sell_orders();
-                sleep_thread(30);
+                cancel_existing_sell_orders(&bracket_book.get_order_ids(), &mut order_pacer);
+                order_pacer.wait();
+
+                // Fills since the last rung shrink or retire each ladder
+                let events: Vec<OrderEvent> = data.arc_order_manager.lock().unwrap().poll();
+                apply_sell_events(&events, &mut system_limit_orders, &bracket_book, &positions);
+                if get_profile_strategy().use_brackets {
+                    bracket_book.apply_events(&events);
+                    save_brackets(&bracket_book);
+                }
 
                 'inner_order_loop: for system_limit_order in &mut system_limit_orders {
+                    if should_stop_work() {
+                        break 'inner_order_loop;
+                    }
+                    heartbeat(&format!(
+                        "repricing {} - step {}",
+                        system_limit_order.limit_order.ticker, i
+                    ));
+
                     let positions: Vec<&Position> = (*positions
                         .iter()
                         .filter(|position| {
@@ -170,108 +514,86 @@ pub mod sell_channel {
                     //     continue 'initial_limit_loop;
                     // }
 
-                    match get_movement_direction(position) {
+                    let sell_price: Price = match get_movement_direction(position) {
                         MOVEMENT_DIRECTION::DOWN => {
                             // For downward trending tickers - priority = minimise losses
-                            let quantity: f64 = position.quantity.clone();
-                            let ticker: String = position.ticker.clone();
-
                             match system_limit_order.sell_attempts {
                                 0 | 1 | 2 => {
                                     println!("\nST: Downward Trending. Creating emergency Break Even limit order for {}...", position.ticker);
                                     // 3 attempts to sell at break even
-                                    create_limit_order(
-                                        ticker,
-                                        position.average_price,
-                                        -quantity,
-                                        TradeDirection::SELL,
-                                    );
+                                    Price::from_f64(position.average_price)
                                 }
                                 3 | 4 => {
                                     println!("\nST: Downward Trending. Creating -0.01% emergency limit order for {}...", position.ticker);
                                     // 3 attempts to sell 0.01% below current price
-                                    create_limit_order(
-                                        ticker,
-                                        get_perc_decrease(position.current_price, 0.01),
-                                        -quantity,
-                                        TradeDirection::SELL,
-                                    );
+                                    get_perc_decrease(position.current_price, 0.01)
                                 }
                                 _ => {
                                     println!("\nST: Downward Trending. Creating -0.05% emergency limit order for {}...", position.ticker);
                                     // Sell 0.05% below current price
-                                    create_limit_order(
-                                        ticker,
-                                        get_perc_decrease(position.current_price, 0.05),
-                                        -quantity,
-                                        TradeDirection::SELL,
-                                    );
+                                    get_perc_decrease(position.current_price, 0.05)
                                 }
-                            };
+                            }
                         }
                         MOVEMENT_DIRECTION::UP => {
                             // For upward trending tickers - priority = maximise gains
-                            let quantity: f64 = position.quantity.clone();
-                            let ticker: String = position.ticker.clone();
-
                             match system_limit_order.sell_attempts {
                                 0 | 1 => {
                                     // Sell at .5% above
                                     println!("\nST: Creating .5% limit order...");
-
-                                    create_limit_order(
-                                        ticker,
-                                        get_perc_increase(position.current_price, 0.5),
-                                        -quantity,
-                                        TradeDirection::SELL,
-                                    );
+                                    get_perc_increase(position.current_price, 0.5)
                                 }
                                 2 | 3 => {
                                     // Sell at .25% above
                                     println!("\nST: Creating .25% limit order...");
-
-                                    create_limit_order(
-                                        ticker,
-                                        get_perc_increase(position.current_price, 0.25),
-                                        -quantity,
-                                        TradeDirection::SELL,
-                                    );
+                                    get_perc_increase(position.current_price, 0.25)
                                 }
                                 4 | 5 => {
                                     // Sell at .1% above
                                     println!("\nST: Creating .1% limit order...");
-
-                                    create_limit_order(
-                                        ticker,
-                                        get_perc_increase(position.current_price, 0.1),
-                                        -quantity,
-                                        TradeDirection::SELL,
-                                    );
+                                    get_perc_increase(position.current_price, 0.1)
                                 }
                                 _ => {
                                     // Sell at current price
                                     println!("\nST: Creating limit sell for current price...");
-
-                                    create_limit_order(
-                                        ticker,
-                                        get_perc_increase(position.current_price, 0.0),
-                                        -quantity,
-                                        TradeDirection::SELL,
-                                    );
+                                    get_perc_increase(position.current_price, 0.0)
                                 }
                             }
                         }
+                    };
+
+                    // Only what is still held - earlier rungs may have filled in part
+                    let quantity: Quantity = Quantity(
+                        Quantity::from_f64(position.quantity).0
+                            - &system_limit_order.sold_quantity.0,
+                    );
+
+                    if let Some(limit_order) = create_limit_order(
+                        position.ticker.clone(),
+                        sell_price,
+                        quantity.negate(),
+                        TradeDirection::SELL,
+                    ) {
+                        data.arc_order_manager
+                            .lock()
+                            .unwrap()
+                            .track_limit_order(&limit_order);
+                        system_limit_order.limit_order = limit_order;
                     }
 
                     system_limit_order.sell_attempts += 1;
-                    sleep_thread(20);
+                    order_pacer.wait();
                 }
-                println!("\nST: Order Loop iteration {} complete. Sleeping...\n", i);
-                // sleep_thread(300);
+                save_ladder_checkpoint(&system_limit_orders);
+                println!("\nST: Order Loop iteration {} complete.\n", i);
             }
 
             println!("ST: New sell orders complete. Signalling Control.");
-            sell_to_ctrl_tx.send(true).unwrap();
+            send_message(
+                sell_to_ctrl_tx,
+                CYCLE_MESSAGE::CYCLE_COMPLETE { id: cycle_id },
+                "Control",
+            );
 
             // TODO
             // // Cycle through closed orders since cycle start time and populate sale result vector
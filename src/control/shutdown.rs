@@ -0,0 +1,147 @@
+pub mod shutdown {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc;
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
+
+    use tokio::signal::unix::{signal, SignalKind};
+
+    use crate::control::checkpoint::checkpoint::{is_cycle_active, save_order_checkpoint};
+    use crate::control::messages::messages::{send_message, CYCLE_MESSAGE};
+    use crate::control::profiles::profiles::set_profile;
+    use crate::control::supervisor::supervisor::are_workers_stopped;
+    use crate::helpers::helpers::helpers::{print_message, sleep_thread, THREAD};
+    use crate::trading212::helpers::helpers::cancel_order;
+    use crate::trading212::orders::orders::OrderManager;
+    use crate::trading212::registry::registry::is_protected_ticker;
+    use crate::{
+        AccountProfile, ChannelParam, SHUTDOWN_GRACE_PERIOD, SHUTDOWN_MODE, SHUTDOWN_POLICY,
+    };
+
+    // How long the workers get to return once told to shut down
+    const WORKER_STOP_TIMEOUT: u64 = 10;
+
+    static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+    // A running profile's shared data and the channel into its Control thread
+    #[derive(Clone)]
+    pub struct ProfileWorkers {
+        pub profile: &'static AccountProfile,
+        pub data: ChannelParam,
+        pub sched_to_ctrl_tx: mpsc::Sender<CYCLE_MESSAGE>,
+    }
+
+    // Once set, no new cycles start and no new buys are placed
+    pub fn is_shutdown_requested() -> bool {
+        SHUTDOWN_REQUESTED.load(Ordering::SeqCst)
+    }
+
+    // True when in-flight work should be abandoned rather than finished
+    pub fn should_stop_work() -> bool {
+        is_shutdown_requested()
+            && match *SHUTDOWN_POLICY {
+                SHUTDOWN_MODE::FINISH => false,
+                SHUTDOWN_MODE::CANCEL | SHUTDOWN_MODE::LEAVE => true,
+            }
+    }
+
+    #[tokio::main]
+    async fn wait_for_signal() -> &'static str {
+        let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+        let mut interrupt = signal(SignalKind::interrupt()).expect("Failed to listen for SIGINT");
+
+        tokio::select! {
+            _ = terminate.recv() => "SIGTERM",
+            _ = interrupt.recv() => "SIGINT",
+        }
+    }
+
+    fn cancel_open_orders(order_manager: &OrderManager) {
+        for order in order_manager.get_open_orders() {
+            if is_protected_ticker(&order.ticker) {
+                continue;
+            }
+            println!("SD: Cancelling open {} order {}...", order.ticker, order.id);
+            if cancel_order(order.id).is_none() {
+                println!("SD: Failed to cancel order {}.", order.id);
+            }
+            sleep_thread(2);
+        }
+    }
+
+    // Waits for SIGINT or SIGTERM, lets the current cycle wind down under SHUTDOWN_POLICY,
+    // stops the workers through Control, checkpoints open orders and exits.
+    // A second signal exits straight away
+    pub fn listen_for_shutdown(profiles: Vec<ProfileWorkers>) -> JoinHandle<()> {
+        thread::Builder::new()
+            .name("Shutdown".to_string())
+            .spawn(move || {
+                let received: &str = wait_for_signal();
+                SHUTDOWN_REQUESTED.store(true, Ordering::SeqCst);
+                print_message(
+                    THREAD::MAIN,
+                    &format!(
+                        "Received {}. Shutting down - {:?}",
+                        received, *SHUTDOWN_POLICY
+                    ),
+                );
+
+                let force_profiles: Vec<ProfileWorkers> = profiles.clone();
+                _ = thread::Builder::new()
+                    .name("Shutdown_Force".to_string())
+                    .spawn(move || {
+                        wait_for_signal();
+                        print_message(THREAD::MAIN, "Received second signal. Exiting now");
+                        for workers in &force_profiles {
+                            set_profile(workers.profile);
+                            if let Ok(order_manager) = workers.data.arc_order_manager.try_lock() {
+                                save_order_checkpoint(&order_manager);
+                            }
+                        }
+                        std::process::exit(1);
+                    });
+
+                let start: Instant = Instant::now();
+                while is_cycle_active()
+                    && start.elapsed() < Duration::from_secs(*SHUTDOWN_GRACE_PERIOD)
+                {
+                    sleep_thread(1);
+                }
+                if is_cycle_active() {
+                    print_message(
+                        THREAD::MAIN,
+                        "Grace period elapsed with the cycle still running",
+                    );
+                }
+
+                // Each Control passes it on to its Buy and Sell
+                for workers in &profiles {
+                    send_message(
+                        &workers.sched_to_ctrl_tx,
+                        CYCLE_MESSAGE::SHUTDOWN,
+                        &format!("{} Control", workers.profile.name),
+                    );
+                }
+                let stop_start: Instant = Instant::now();
+                while !are_workers_stopped()
+                    && stop_start.elapsed() < Duration::from_secs(WORKER_STOP_TIMEOUT)
+                {
+                    sleep_thread(1);
+                }
+
+                // Orders are cancelled and checkpointed with each profile's own key and data directory
+                for workers in &profiles {
+                    set_profile(workers.profile);
+                    let order_manager = workers.data.arc_order_manager.lock().unwrap();
+                    if let SHUTDOWN_MODE::CANCEL = *SHUTDOWN_POLICY {
+                        cancel_open_orders(&order_manager);
+                    }
+                    save_order_checkpoint(&order_manager);
+                }
+
+                print_message(THREAD::MAIN, "Checkpoint saved. Apollo stopped");
+                std::process::exit(0);
+            })
+            .expect("[Main Thread] Failed to spawn Shutdown thread")
+    }
+}
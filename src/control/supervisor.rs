@@ -0,0 +1,304 @@
+pub mod supervisor {
+    use std::collections::HashMap;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::mpsc::{self, RecvTimeoutError};
+    use std::sync::Mutex;
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
+
+    use chrono::Utc;
+    use lazy_static::lazy_static;
+    use serde::{Deserialize, Serialize};
+
+    use crate::control::scheduler::scheduler::{get_job_statuses, JobStatus};
+    use crate::control::shutdown::shutdown::is_shutdown_requested;
+    use crate::helpers::helpers::helpers::{print_message, sleep_thread, THREAD};
+    use crate::{
+        HEARTBEAT_INTERVAL, WORKER_MAX_RESTARTS, WORKER_RESTART_DELAY, WORKER_STABLE_RUN,
+        WORKER_STALL_TIMEOUT,
+    };
+
+    const HEALTH_PATH: &str = "src/data/health.json";
+
+    lazy_static! {
+        // Supervised workers by thread name
+        static ref WORKERS: Mutex<HashMap<String, WorkerHealth>> = Mutex::new(HashMap::new());
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+    pub enum WORKER_STATE {
+        RUNNING,
+        RESTARTING, // Panicked or returned - waiting WORKER_RESTART_DELAY before starting again
+        STALLED,    // No heartbeat for WORKER_STALL_TIMEOUT
+        DEAD,       // Gave up after WORKER_MAX_RESTARTS
+        STOPPED,    // Returned during shutdown - not restarted
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct WorkerHealth {
+        pub name: String,
+        pub state: WORKER_STATE,
+        pub activity: String, // What the worker said it was doing at its last heartbeat
+        pub last_heartbeat: i64,
+        pub restarts: u32,
+        pub last_failure: Option<String>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Default)]
+    pub struct HealthReport {
+        pub checked_at: i64,
+        pub healthy: bool,
+        pub workers: Vec<WorkerHealth>,
+        pub jobs: Vec<JobStatus>,
+    }
+
+    fn update_worker(name: &str, update: impl FnOnce(&mut WorkerHealth)) {
+        let mut workers = WORKERS.lock().unwrap();
+        if let Some(worker) = workers.get_mut(name) {
+            update(worker);
+        }
+    }
+
+    // Records that the calling worker is alive. Calls from unsupervised threads are ignored
+    pub fn heartbeat(activity: &str) {
+        let name: String = match thread::current().name() {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        update_worker(&name, |worker| {
+            worker.activity = activity.to_string();
+            worker.last_heartbeat = Utc::now().timestamp();
+            if worker.state == WORKER_STATE::STALLED {
+                worker.state = WORKER_STATE::RUNNING;
+            }
+        });
+    }
+
+    // Waits on another thread, heartbeating while it does.
+    // Gives up after timeout seconds, or never when timeout is None
+    pub fn await_signal<T>(
+        receiver: &mpsc::Receiver<T>,
+        waiting_for: &str,
+        timeout: Option<u64>,
+    ) -> Option<T> {
+        let start: Instant = Instant::now();
+        let activity: String = format!("waiting for {}", waiting_for);
+
+        loop {
+            heartbeat(&activity);
+            match receiver.recv_timeout(Duration::from_secs(*HEARTBEAT_INTERVAL)) {
+                Ok(message) => return Some(message),
+                Err(RecvTimeoutError::Disconnected) => {
+                    println!("SV: Channel closed while {}.", activity);
+                    return None;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    if timeout.is_some_and(|timeout| start.elapsed().as_secs() >= timeout) {
+                        println!(
+                            "SV: Timed out after {}s {}.",
+                            start.elapsed().as_secs(),
+                            activity
+                        );
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+
+    // True once no supervised worker is still running or about to restart
+    pub fn are_workers_stopped() -> bool {
+        WORKERS.lock().unwrap().values().all(|worker| {
+            worker.state == WORKER_STATE::STOPPED || worker.state == WORKER_STATE::DEAD
+        })
+    }
+
+    fn get_panic_message(payload: &Box<dyn std::any::Any + Send>) -> String {
+        match payload.downcast_ref::<&str>() {
+            Some(message) => message.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => String::from("unknown panic"),
+            },
+        }
+    }
+
+    // Runs a worker on its own thread and starts it again whenever it panics or returns.
+    // on_restart runs before each restart - used to clear locks the failed run poisoned
+    pub fn spawn_supervised(
+        name: &str,
+        worker: impl Fn() + Send + 'static,
+        on_restart: impl Fn() + Send + 'static,
+    ) -> JoinHandle<()> {
+        WORKERS.lock().unwrap().insert(
+            name.to_string(),
+            WorkerHealth {
+                name: name.to_string(),
+                state: WORKER_STATE::RUNNING,
+                activity: String::from("starting"),
+                last_heartbeat: Utc::now().timestamp(),
+                restarts: 0,
+                last_failure: None,
+            },
+        );
+
+        let worker_name: String = name.to_string();
+        thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || loop {
+                let started: Instant = Instant::now();
+                let failure: String = match panic::catch_unwind(AssertUnwindSafe(|| worker())) {
+                    Ok(_) if is_shutdown_requested() => {
+                        print_message(THREAD::MAIN, &format!("{} stopped", worker_name));
+                        update_worker(&worker_name, |worker| worker.state = WORKER_STATE::STOPPED);
+                        return;
+                    }
+                    Ok(_) => String::from("returned unexpectedly"),
+                    Err(payload) => format!("panicked - {}", get_panic_message(&payload)),
+                };
+
+                // Only failures close together count towards giving up - a worker that
+                // ran fine for a while before failing starts over
+                if started.elapsed() >= Duration::from_secs(*WORKER_STABLE_RUN) {
+                    update_worker(&worker_name, |worker| worker.restarts = 0);
+                }
+
+                let restarts: u32 = WORKERS
+                    .lock()
+                    .unwrap()
+                    .get(&worker_name)
+                    .map(|worker| worker.restarts)
+                    .unwrap_or_default();
+
+                if restarts >= *WORKER_MAX_RESTARTS {
+                    print_message(
+                        THREAD::MAIN,
+                        &format!(
+                            "{} {} after {} restarts. Giving up",
+                            worker_name, failure, restarts
+                        ),
+                    );
+                    update_worker(&worker_name, |worker| {
+                        worker.state = WORKER_STATE::DEAD;
+                        worker.last_failure = Some(failure);
+                    });
+                    return;
+                }
+
+                print_message(
+                    THREAD::MAIN,
+                    &format!(
+                        "{} {}. Restarting in {}s",
+                        worker_name, failure, *WORKER_RESTART_DELAY
+                    ),
+                );
+                update_worker(&worker_name, |worker| {
+                    worker.state = WORKER_STATE::RESTARTING;
+                    worker.restarts += 1;
+                    worker.last_failure = Some(failure);
+                });
+
+                sleep_thread(*WORKER_RESTART_DELAY);
+                on_restart();
+                update_worker(&worker_name, |worker| {
+                    worker.state = WORKER_STATE::RUNNING;
+                    worker.last_heartbeat = Utc::now().timestamp();
+                });
+            })
+            .expect("[Main Thread] Failed to spawn supervised worker")
+    }
+
+    pub fn get_health_report() -> HealthReport {
+        let now: i64 = Utc::now().timestamp();
+        let mut workers: Vec<WorkerHealth> = WORKERS.lock().unwrap().values().cloned().collect();
+        workers.sort_by(|a, b| a.name.cmp(&b.name));
+
+        HealthReport {
+            checked_at: now,
+            healthy: workers
+                .iter()
+                .all(|worker| worker.state == WORKER_STATE::RUNNING),
+            workers,
+            jobs: get_job_statuses(),
+        }
+    }
+
+    fn save_health_report(report: &HealthReport) {
+        let contents: String =
+            serde_json::to_string_pretty(report).expect("Health report serialization failed");
+
+        if std::fs::write(HEALTH_PATH, contents).is_err() {
+            print_message(THREAD::FILE, "Failed to write health report");
+        }
+    }
+
+    fn print_health_report(report: &HealthReport) {
+        let now: i64 = Utc::now().timestamp();
+        for worker in &report.workers {
+            println!(
+                "SV: {} {:?} - {} ({}s ago), restarts: {}",
+                worker.name,
+                worker.state,
+                worker.activity,
+                now - worker.last_heartbeat,
+                worker.restarts
+            );
+        }
+    }
+
+    // Marks workers without a recent heartbeat as stalled and writes the health file every HEARTBEAT_INTERVAL
+    pub fn start_health_monitor() -> JoinHandle<()> {
+        thread::Builder::new()
+            .name("Health_Monitor".to_string())
+            .spawn(move || loop {
+                sleep_thread(*HEARTBEAT_INTERVAL);
+
+                let now: i64 = Utc::now().timestamp();
+                for worker in WORKERS.lock().unwrap().values_mut() {
+                    let silent_for: i64 = now - worker.last_heartbeat;
+                    if worker.state == WORKER_STATE::RUNNING
+                        && silent_for >= *WORKER_STALL_TIMEOUT as i64
+                    {
+                        println!(
+                            "SV: {} has not sent a heartbeat for {}s - last {}.",
+                            worker.name, silent_for, worker.activity
+                        );
+                        worker.state = WORKER_STATE::STALLED;
+                    }
+                }
+
+                let report: HealthReport = get_health_report();
+                print_health_report(&report);
+                save_health_report(&report);
+            })
+            .expect("[Main Thread] Failed to spawn Health Monitor thread")
+    }
+
+    // apollo health - prints the last health report and exits non-zero if anything is unhealthy
+    pub fn run_health_command() -> bool {
+        let report: HealthReport = match std::fs::read_to_string(HEALTH_PATH)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+        {
+            Some(report) => report,
+            None => {
+                println!("No health report found. Is Apollo running?");
+                return false;
+            }
+        };
+
+        let age: i64 = Utc::now().timestamp() - report.checked_at;
+        println!("Health report from {}s ago.", age);
+        print_health_report(&report);
+        for job in &report.jobs {
+            println!(
+                "  {} - running: {}, missed runs: {}",
+                job.name, job.running, job.missed_runs
+            );
+        }
+
+        // A report that has stopped updating means the whole process is gone
+        let is_fresh: bool = age < (*HEARTBEAT_INTERVAL as i64) * 3;
+        report.healthy && is_fresh
+    }
+}
@@ -0,0 +1,77 @@
+pub mod cache {
+    use chrono::Utc;
+    use serde::{Deserialize, Serialize};
+
+    use crate::finnhub::utils::helpers::{CompanyInfo, FHStockData};
+    use crate::helpers::helpers::helpers::{print_message, THREAD};
+    use crate::helpers::types::types::{
+        CompanyFinancials, EarningsRelease, InsiderTransaction, NewsArticle, SentimentData,
+    };
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct CachedField<T> {
+        pub fetched_at: i64, // Unix seconds
+        pub value: T,
+    }
+
+    // Everything get_full_company_info collects for a single Finnhub symbol.
+    // Each field expires on its own TTL so only stale data is refetched
+    #[derive(Debug, Serialize, Deserialize, Default)]
+    pub struct CachedCompanyInfo {
+        pub company_info: Option<CachedField<CompanyInfo>>,
+        pub quote: Option<CachedField<FHStockData>>,
+        pub insider_transactions: Option<CachedField<Vec<InsiderTransaction>>>,
+        pub news: Option<CachedField<Vec<NewsArticle>>>,
+        pub peers: Option<CachedField<Vec<String>>>,
+        pub financials: Option<CachedField<CompanyFinancials>>,
+        pub sentiment: Option<CachedField<Vec<SentimentData>>>,
+        pub earnings_calendar: Option<CachedField<Vec<EarningsRelease>>>,
+    }
+
+    fn get_cache_path(fh_ticker: &str) -> String {
+        format!("src/data/company_cache/{}.json", fh_ticker)
+    }
+
+    pub fn load_company_cache(fh_ticker: &str) -> CachedCompanyInfo {
+        match std::fs::read_to_string(get_cache_path(fh_ticker)).ok() {
+            // A cache written by an older layout is simply rebuilt
+            Some(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+            None => CachedCompanyInfo::default(),
+        }
+    }
+
+    pub fn save_company_cache(fh_ticker: &str, cache: &CachedCompanyInfo) {
+        std::fs::create_dir_all("src/data/company_cache")
+            .expect("Failed to create company cache directory.");
+
+        let cache_data: String =
+            serde_json::to_string(cache).expect("Company cache serialization failed");
+
+        if std::fs::write(get_cache_path(fh_ticker), cache_data).is_err() {
+            print_message(THREAD::FILE, "Failed to write company cache");
+        }
+    }
+
+    // Returns the cached value while it is younger than the TTL, otherwise fetches and stores a new one.
+    // Failed fetches are not cached so they are retried next time
+    pub fn get_or_refresh<T: Clone>(
+        field: &mut Option<CachedField<T>>,
+        ttl_seconds: u64,
+        fetch: impl FnOnce() -> Option<T>,
+    ) -> Option<T> {
+        let now: i64 = Utc::now().timestamp();
+
+        if let Some(cached) = field {
+            if now - cached.fetched_at < ttl_seconds as i64 {
+                return Some(cached.value.clone());
+            }
+        }
+
+        let value: T = fetch()?;
+        *field = Some(CachedField {
+            fetched_at: now,
+            value: value.clone(),
+        });
+        Some(value)
+    }
+}
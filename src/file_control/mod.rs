@@ -1,3 +1,4 @@
+pub mod cache;
 pub mod read;
 pub mod types;
 pub mod write;
@@ -2,17 +2,21 @@ pub mod read {
     use std::path::PathBuf;
 
     use crate::{
+        control::profiles::profiles::get_profile_path,
         file_control::types::types::{
-            file_current_trading212_positions_data, file_instrument_data,
+            file_current_trading212_positions_data, file_exchange_data, file_instrument_data,
+            file_screening_data, file_watchlist_data, InstrumentDiff, SaleResult,
         },
         helpers::{
             helpers::helpers::{
                 get_current_date, get_current_hours, is_before_today, is_first_date_before,
                 print_message, THREAD,
             },
+            screening::screening::ScreeningResult,
             types::types::{DateType, FullCompanyInfo},
+            watchlists::watchlists::{add_to_watchlist, Watchlist},
         },
-        trading212::types::types::{Instrument, Position},
+        trading212::types::types::{Exchange, Instrument, Position},
     };
 
     pub fn get_dir_path() -> PathBuf {
@@ -25,7 +29,7 @@ pub mod read {
         println!("\nST: Reading position data from file...");
 
         let read_positions: String =
-            match std::fs::read_to_string("src/data/current_positions.json").ok() {
+            match std::fs::read_to_string(get_profile_path("current_positions.json")).ok() {
                 None => {
                     println!("ST: None found.");
                     return None;
@@ -80,6 +84,95 @@ pub mod read {
         }
     }
 
+    // Whatever list is on file, however old - used to diff against a fresh download
+    pub fn get_previous_instruments_from_file() -> Option<(String, Vec<Instrument>)> {
+        let read_instruments: String = std::fs::read_to_string("src/data/instruments.json").ok()?;
+
+        match serde_json::from_str::<file_instrument_data>(&read_instruments) {
+            Ok(instrument_data) => {
+                Some((instrument_data.creation_date, instrument_data.instruments))
+            }
+            Err(_) => None,
+        }
+    }
+
+    pub fn get_instrument_diffs_from_file() -> Option<Vec<InstrumentDiff>> {
+        let read_diffs: String = std::fs::read_to_string("src/data/instrument_diffs.json").ok()?;
+        serde_json::from_str::<Vec<InstrumentDiff>>(&read_diffs).ok()
+    }
+
+    pub fn get_sale_results_from_file() -> Option<Vec<SaleResult>> {
+        let read_sales: String =
+            std::fs::read_to_string(get_profile_path("sale_results.json")).ok()?;
+        serde_json::from_str::<Vec<SaleResult>>(&read_sales).ok()
+    }
+
+    pub fn get_exchanges_from_file() -> Option<Vec<Exchange>> {
+        print_message(THREAD::FILE, "Reading exchange schedules from file...");
+
+        let read_exchanges: String = match std::fs::read_to_string("src/data/exchanges.json").ok() {
+            None => {
+                print_message(THREAD::FILE, "None found.");
+                return None;
+            }
+            Some(read_exchanges) => read_exchanges,
+        };
+
+        match serde_json::from_str::<file_exchange_data>(&read_exchanges) {
+            Ok(exchange_data) => {
+                // Schedules only cover the coming days so stale data is treated as missing
+                if is_before_today(&exchange_data.creation_date) {
+                    return None;
+                }
+                return Some(exchange_data.exchanges);
+            }
+            Err(_) => {
+                print_message(THREAD::FILE, "None found.");
+                return None;
+            }
+        }
+    }
+
+    // Latest passed candidates, best ranked first. They may come from a run still in progress or
+    // from an earlier day - the buy thread checks each against a fresh quote before buying
+    pub fn get_screening_results_from_file() -> Option<Vec<ScreeningResult>> {
+        print_message(THREAD::FILE, "Reading screening results from file...");
+
+        let read_results: String =
+            match std::fs::read_to_string("src/data/screening_results.json").ok() {
+                None => {
+                    print_message(THREAD::FILE, "None found.");
+                    return None;
+                }
+                Some(read_results) => read_results,
+            };
+
+        match serde_json::from_str::<file_screening_data>(&read_results) {
+            Ok(screening_data) => {
+                if is_before_today(&screening_data.creation_date) {
+                    print_message(
+                        THREAD::FILE,
+                        &format!(
+                            "Using screening results from {}",
+                            screening_data.creation_date
+                        ),
+                    );
+                }
+                return Some(
+                    screening_data
+                        .results
+                        .into_iter()
+                        .filter(|result| result.passed)
+                        .collect(),
+                );
+            }
+            Err(_) => {
+                print_message(THREAD::FILE, "None found.");
+                return None;
+            }
+        }
+    }
+
     pub fn get_buy_list_from_file() -> Option<Vec<FullCompanyInfo>> {
         println!("\nBT: Reading buy list data from file...");
 
@@ -119,6 +212,18 @@ pub mod read {
     }
 
     pub fn add_stock_to_personal_list(company: FullCompanyInfo) {
-        let dir: PathBuf = get_docs_dir_path();
+        add_to_watchlist("personal", &company.instrument.ticker);
+    }
+
+    pub fn get_watchlists_from_file() -> Option<Vec<Watchlist>> {
+        let read_watchlists: String = std::fs::read_to_string("src/data/watchlists.json").ok()?;
+
+        match serde_json::from_str::<file_watchlist_data>(&read_watchlists) {
+            Ok(watchlist_data) => Some(watchlist_data.watchlists),
+            Err(_) => {
+                print_message(THREAD::FILE, "Failed to read watchlists.");
+                None
+            }
+        }
     }
 }
@@ -1,7 +1,9 @@
 pub mod types {
     use serde::{Deserialize, Serialize};
 
-    use crate::trading212::types::types::{HistoricalOrder, Instrument, Position};
+    use crate::helpers::screening::screening::ScreeningResult;
+    use crate::helpers::watchlists::watchlists::Watchlist;
+    use crate::trading212::types::types::{Exchange, HistoricalOrder, Instrument, Position};
 
     #[derive(Debug, Serialize, Deserialize)]
     pub struct file_current_trading212_positions_data {
@@ -15,6 +17,41 @@ pub mod types {
         pub instruments: Vec<Instrument>,
     }
 
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct file_exchange_data {
+        pub creation_date: String,
+        pub exchanges: Vec<Exchange>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct file_screening_data {
+        pub creation_date: String,
+        pub results: Vec<ScreeningResult>, // Passed first, best score first
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct file_watchlist_data {
+        pub watchlists: Vec<Watchlist>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct InstrumentChange {
+        pub ticker: String,
+        pub field: String, // "max_open_quantity" or "min_trade_quantity"
+        pub previous: f64,
+        pub current: f64,
+    }
+
+    // Difference between two consecutive daily instrument lists
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct InstrumentDiff {
+        pub creation_date: String,
+        pub previous_date: String,
+        pub added: Vec<Instrument>,    // Newest addedOn first
+        pub delisted: Vec<Instrument>, // Present yesterday, gone today
+        pub changed: Vec<InstrumentChange>,
+    }
+
     #[derive(Debug, Serialize, Deserialize)]
     pub struct CycleResult {
         pub start_time: String,
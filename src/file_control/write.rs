@@ -10,14 +10,26 @@ pub mod write {
     use serde_json::{from_str, to_string_pretty};
 
     use crate::{
+        control::profiles::profiles::get_profile_path,
+        control::reconciliation::reconciliation::Discrepancy,
+        control::scheduler::scheduler::MissedRun,
+        file_control::read::read::get_instrument_diffs_from_file,
         file_control::types::types::{
-            file_current_trading212_positions_data, file_instrument_data, CycleResult, SaleResult,
+            file_current_trading212_positions_data, file_exchange_data, file_instrument_data,
+            file_screening_data, file_watchlist_data, CycleResult, InstrumentDiff, SaleResult,
         },
         helpers::{
             helpers::helpers::{get_current_date, get_current_time, print_message, THREAD},
+            screening::screening::ScreeningResult,
+            sentiment::sentiment::NewsSentiment,
             types::types::{DateType, FullCompanyInfo},
+            watchlists::watchlists::Watchlist,
         },
-        trading212::types::types::{BalanceObject, HistoricalOrder, Instrument, Position},
+        trading212::orders::orders::OrderEvent,
+        trading212::types::types::{
+            BalanceObject, Exchange, HistoricalOrder, Instrument, Position,
+        },
+        INSTRUMENT_DIFF_HISTORY,
     };
 
     ////////////////////////// Trading 212 //////////////////////////////////
@@ -33,8 +45,11 @@ pub mod write {
         let position_data_list: String =
             serde_json::to_string_pretty(&position_data).expect("Instruments serialization failed");
 
-        std::fs::write("src/data/current_positions.json", position_data_list)
-            .expect("ST: Failed to write positions to file.");
+        std::fs::write(
+            get_profile_path("current_positions.json"),
+            position_data_list,
+        )
+        .expect("ST: Failed to write positions to file.");
         println!("ST: Done.");
     }
 
@@ -101,6 +116,47 @@ pub mod write {
         print_message(THREAD::FILE, "Done.");
     }
 
+    // Appends to "src/data/instrument_diffs.json", keeping the latest INSTRUMENT_DIFF_HISTORY entries
+    pub fn log_instrument_diff(diff: InstrumentDiff) {
+        print_message(THREAD::FILE, "Writing instrument diff to file...");
+
+        let mut history: Vec<InstrumentDiff> = get_instrument_diffs_from_file().unwrap_or_default();
+
+        // A second download on the same day replaces that day's entry
+        history.retain(|entry| entry.creation_date != diff.creation_date);
+        history.push(diff);
+
+        let excess: usize = history.len().saturating_sub(*INSTRUMENT_DIFF_HISTORY);
+        history.drain(..excess);
+
+        let history_data: String =
+            to_string_pretty(&history).expect("Instrument diff serialization failed");
+
+        if std::fs::write("src/data/instrument_diffs.json", history_data).is_err() {
+            print_message(THREAD::FILE, "Failed to write instrument diff");
+            return;
+        }
+        print_message(THREAD::FILE, "Done.");
+    }
+
+    pub fn write_exchanges_to_file(exchanges: Vec<Exchange>) {
+        print_message(THREAD::FILE, "Writing exchange schedules to file...");
+
+        std::fs::create_dir_all("src/data").expect("Failed to create data directory.");
+
+        let exchange_data: file_exchange_data = file_exchange_data {
+            creation_date: get_current_date(DateType::DMY),
+            exchanges,
+        };
+
+        let exchange_list: String =
+            serde_json::to_string_pretty(&exchange_data).expect("Exchanges serialization failed");
+
+        std::fs::write("src/data/exchanges.json", exchange_list)
+            .expect("Failed to write exchanges to file.");
+        print_message(THREAD::FILE, "Done.");
+    }
+
     pub fn write_filtered_instruments_to_file(instruments: Vec<Instrument>) {
         print_message(THREAD::FILE, "Writing filtered instrument list to file...");
 
@@ -146,17 +202,145 @@ pub mod write {
         print_message(THREAD::FILE, "Done.");
     }
 
+    pub fn write_screening_results_to_file(results: &Vec<ScreeningResult>) {
+        print_message(THREAD::FILE, "Writing screening results to file...");
+
+        std::fs::create_dir_all("src/data").expect("Failed to create data directory.");
+
+        let screening_data: file_screening_data = file_screening_data {
+            creation_date: get_current_date(DateType::DMY),
+            results: results.clone(),
+        };
+
+        let screening_list: String = serde_json::to_string_pretty(&screening_data)
+            .expect("Screening results serialization failed");
+
+        // The buy thread reads this while screening republishes it, so the new results are
+        // written beside it and renamed over it - a reader sees the old file or the new one
+        let temp_path: &str = "src/data/screening_results.json.tmp";
+        std::fs::write(temp_path, screening_list)
+            .expect("Failed to write screening results to file.");
+        std::fs::rename(temp_path, "src/data/screening_results.json")
+            .expect("Failed to replace the screening results file.");
+
+        print_message(THREAD::FILE, "Done.");
+    }
+
+    pub fn write_watchlists_to_file(watchlists: &Vec<Watchlist>) {
+        std::fs::create_dir_all("src/data").expect("Failed to create data directory.");
+
+        let watchlist_data: file_watchlist_data = file_watchlist_data {
+            watchlists: watchlists.clone(),
+        };
+
+        let watchlist_list: String =
+            serde_json::to_string_pretty(&watchlist_data).expect("Watchlist serialization failed");
+
+        std::fs::write("src/data/watchlists.json", watchlist_list)
+            .expect("Failed to write watchlists to file.");
+    }
+
+    pub fn write_sentiment_audit(ticker: &str, sentiment: &NewsSentiment) {
+        // Latest per article scores for each ticker - "src/data/sentiment/{ticker}.json"
+        std::fs::create_dir_all("src/data/sentiment")
+            .expect("Failed to create sentiment directory.");
+
+        let sentiment_data: String =
+            serde_json::to_string_pretty(sentiment).expect("Sentiment serialization failed");
+
+        if std::fs::write(
+            format!("src/data/sentiment/{}.json", ticker),
+            sentiment_data,
+        )
+        .is_err()
+        {
+            print_message(THREAD::FILE, "Failed to write sentiment audit");
+        }
+    }
+
     pub fn write_buy_list_to_file(instruments: &Vec<FullCompanyInfo>) {
         println!("\nBT: Writing buy list to file...");
 
         let instrument_list: String =
             serde_json::to_string_pretty(instruments).expect("Instruments serialization failed");
 
-        std::fs::write("src/data/buy_list.json", instrument_list)
+        std::fs::write(get_profile_path("buy_list.json"), instrument_list)
             .expect("BT: Failed to write buy list to file.");
         println!("BT: Done.");
     }
 
+    // Appends to "order_events.json" in the profile's data directory
+    pub fn log_order_events(events: &Vec<OrderEvent>) {
+        let file_path: &str = &get_profile_path("order_events.json");
+
+        let mut data: Vec<OrderEvent> = match std::fs::read_to_string(file_path).ok() {
+            Some(contents) => from_str(&contents).unwrap_or_default(),
+            None => Vec::new(),
+        };
+        data.extend(events.iter().cloned());
+
+        let updated_json: String =
+            to_string_pretty(&data).expect("Failed to serialize order events to JSON");
+
+        if std::fs::write(file_path, updated_json).is_err() {
+            print_message(THREAD::FILE, "Failed to write order events");
+        }
+    }
+
+    // Appends to "reconciliation_log.json" in the profile's data directory
+    pub fn log_discrepancies(discrepancies: &Vec<Discrepancy>) {
+        let file_path: &str = &get_profile_path("reconciliation_log.json");
+
+        let mut data: Vec<Discrepancy> = match std::fs::read_to_string(file_path).ok() {
+            Some(contents) => from_str(&contents).unwrap_or_default(),
+            None => Vec::new(),
+        };
+        data.extend(discrepancies.iter().cloned());
+
+        let updated_json: String =
+            to_string_pretty(&data).expect("Failed to serialize discrepancies to JSON");
+
+        if std::fs::write(file_path, updated_json).is_err() {
+            print_message(THREAD::FILE, "Failed to write reconciliation log");
+        }
+    }
+
+    // Appends to "src/data/missed_runs.json"
+    pub fn log_missed_runs(missed_runs: &Vec<MissedRun>) {
+        let file_path: &str = "src/data/missed_runs.json";
+
+        let mut data: Vec<MissedRun> = match std::fs::read_to_string(file_path).ok() {
+            Some(contents) => from_str(&contents).unwrap_or_default(),
+            None => Vec::new(),
+        };
+        data.extend(missed_runs.iter().cloned());
+
+        let updated_json: String =
+            to_string_pretty(&data).expect("Failed to serialize missed runs to JSON");
+
+        if std::fs::write(file_path, updated_json).is_err() {
+            print_message(THREAD::FILE, "Failed to write missed runs log");
+        }
+    }
+
+    // Appends to "sale_results.json" in the profile's data directory - the profile's trade journal
+    pub fn log_sale_result(sale: SaleResult) {
+        let file_path: &str = &get_profile_path("sale_results.json");
+
+        let mut data: Vec<SaleResult> = match std::fs::read_to_string(file_path).ok() {
+            Some(contents) => from_str(&contents).unwrap_or_default(),
+            None => Vec::new(),
+        };
+        data.push(sale);
+
+        let updated_json: String =
+            to_string_pretty(&data).expect("Failed to serialize sale results to JSON");
+
+        if std::fs::write(file_path, updated_json).is_err() {
+            print_message(THREAD::FILE, "Failed to write sale result");
+        }
+    }
+
     pub fn log_cycle_result(start_time: String, results: Vec<HistoricalOrder>, total_profit: f64) {
         println!(
             "\n--------------------- S: PROFIT THIS CYCLE: {} ---------------------\n",
@@ -172,7 +356,7 @@ pub mod write {
 
         // Pull down data, append to sales vector, and write back to json file
 
-        let file_path: &str = "src/data/result_list.json";
+        let file_path: &str = &get_profile_path("result_list.json");
 
         let mut data: Vec<CycleResult> = if Path::new(file_path).exists() {
             let mut file = File::open(file_path).expect("Failed to open results file.");
@@ -1,4 +1,5 @@
 pub mod helpers;
 pub mod finnhub;
+pub mod symbols;
 pub mod types;
 pub mod utils;
@@ -0,0 +1,253 @@
+pub mod symbols {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::file_control::read::read::get_exchanges_from_file;
+    use crate::finnhub::utils::helpers::convert_to_fh_ticker;
+    use crate::helpers::helpers::helpers::{get_current_date, print_message, THREAD};
+    use crate::helpers::listings::listings::get_listed_instrument;
+    use crate::helpers::types::types::DateType;
+    use crate::trading212::schedules::schedules::ScheduleCalendar;
+    use crate::trading212::types::types::Instrument;
+
+    // Hand maintained Trading212 ticker -> Finnhub symbol entries. Always win over derived symbols
+    const OVERRIDES_PATH: &str = "src/data/fh_symbol_overrides.json";
+    const CACHE_PATH: &str = "src/data/fh_symbol_cache.json";
+    const UNMAPPED_PATH: &str = "src/data/fh_unmapped.json";
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct UnmappedInstrument {
+        pub ticker: String,
+        pub isin: String,
+        pub currency_code: String,
+        pub exchange: Option<String>,
+        pub last_seen: String,
+    }
+
+    fn read_symbol_map(path: &str) -> HashMap<String, String> {
+        match std::fs::read_to_string(path).ok() {
+            Some(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+            None => HashMap::new(),
+        }
+    }
+
+    fn write_json<T: Serialize>(path: &str, data: &T) {
+        std::fs::create_dir_all("src/data").expect("Failed to create data directory.");
+        let contents: String = serde_json::to_string_pretty(data).expect("Serialization failed");
+        if std::fs::write(path, contents).is_err() {
+            print_message(THREAD::FILE, &format!("Failed to write {}", path));
+        }
+    }
+
+    // Trading212 marks non US listings with a lowercase venue letter before "_EQ" (VODl_EQ, SAPd_EQ)
+    fn split_venue_suffix(ticker: &str) -> Option<(String, char)> {
+        let base: &str = ticker.strip_suffix("_EQ")?;
+        if base.ends_with("_US") {
+            return None;
+        }
+
+        let venue: char = base.chars().last()?;
+        if !venue.is_ascii_lowercase() {
+            return None;
+        }
+
+        Some((base[..base.len() - 1].to_string(), venue))
+    }
+
+    fn fh_suffix_from_venue(venue: char) -> Option<&'static str> {
+        match venue {
+            'l' => Some(".L"),  // London Stock Exchange
+            'd' => Some(".DE"), // Deutsche Börse Xetra
+            'p' => Some(".PA"), // Euronext Paris
+            'a' => Some(".AS"), // Euronext Amsterdam
+            'b' => Some(".BR"), // Euronext Brussels
+            'e' => Some(".MC"), // Bolsa de Madrid
+            'm' => Some(".MI"), // Borsa Italiana
+            's' => Some(".SW"), // SIX Swiss Exchange
+            'v' => Some(".VI"), // Wiener Börse
+            'k' => Some(".ST"), // Nasdaq Stockholm
+            _ => None,
+        }
+    }
+
+    fn fh_suffix_from_exchange(exchange_name: &str) -> Option<&'static str> {
+        let name: String = exchange_name.to_ascii_lowercase();
+
+        if name.contains("nyse") || name.contains("nasdaq") || name.contains("otc") {
+            return Some("");
+        }
+        if name.contains("london") {
+            return Some(".L");
+        }
+        if name.contains("xetra") || name.contains("frankfurt") || name.contains("deutsche") {
+            return Some(".DE");
+        }
+        if name.contains("paris") {
+            return Some(".PA");
+        }
+        if name.contains("amsterdam") {
+            return Some(".AS");
+        }
+        if name.contains("brussels") {
+            return Some(".BR");
+        }
+        if name.contains("madrid") {
+            return Some(".MC");
+        }
+        if name.contains("italiana") || name.contains("milan") {
+            return Some(".MI");
+        }
+        if name.contains("swiss") || name.contains("six") {
+            return Some(".SW");
+        }
+        if name.contains("vienna") || name.contains("wiener") {
+            return Some(".VI");
+        }
+        None
+    }
+
+    // Last resort for listings with no venue letter - only used where the pairing is unambiguous
+    fn fh_suffix_from_listing(isin: &str, currency_code: &str) -> Option<&'static str> {
+        let country: &str = isin.get(0..2).unwrap_or("");
+
+        match (country, currency_code) {
+            ("US", "USD") => Some(""),
+            (_, "GBX") | (_, "GBP") => Some(".L"),
+            ("DE", "EUR") => Some(".DE"),
+            ("FR", "EUR") => Some(".PA"),
+            ("NL", "EUR") => Some(".AS"),
+            ("BE", "EUR") => Some(".BR"),
+            ("ES", "EUR") => Some(".MC"),
+            ("IT", "EUR") => Some(".MI"),
+            ("AT", "EUR") => Some(".VI"),
+            ("CH", "CHF") => Some(".SW"),
+            _ => None,
+        }
+    }
+
+    fn to_fh_base(base: &str) -> String {
+        // Share classes use a dot on Finnhub (BRK_B -> BRK.B)
+        base.to_ascii_uppercase().replace('_', ".")
+    }
+
+    pub fn derive_fh_symbol(
+        instrument: &Instrument,
+        exchange_name: Option<&str>,
+    ) -> Option<String> {
+        if instrument.ticker.ends_with("_US_EQ") {
+            let base: String = instrument.ticker.trim_end_matches("_US_EQ").to_string();
+            return Some(to_fh_base(&base));
+        }
+
+        if let Some((base, venue)) = split_venue_suffix(&instrument.ticker) {
+            if let Some(suffix) = fh_suffix_from_venue(venue) {
+                return Some(format!("{}{}", to_fh_base(&base), suffix));
+            }
+        }
+
+        let base: String = convert_to_fh_ticker(&instrument.ticker);
+
+        if let Some(suffix) = exchange_name.and_then(fh_suffix_from_exchange) {
+            return Some(format!("{}{}", to_fh_base(&base), suffix));
+        }
+
+        fh_suffix_from_listing(&instrument.isin, &instrument.currency_code)
+            .map(|suffix| format!("{}{}", to_fh_base(&base), suffix))
+    }
+
+    fn record_unmapped(entry: UnmappedInstrument) {
+        let mut unmapped: HashMap<String, UnmappedInstrument> =
+            match std::fs::read_to_string(UNMAPPED_PATH).ok() {
+                Some(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+                None => HashMap::new(),
+            };
+
+        unmapped.insert(entry.ticker.clone(), entry);
+
+        write_json(UNMAPPED_PATH, &unmapped);
+    }
+
+    fn report_unmapped(instrument: &Instrument, exchange: Option<String>) {
+        println!(
+            "ST: No Finnhub symbol for {} ({} - {}).",
+            instrument.ticker, instrument.isin, instrument.currency_code
+        );
+
+        record_unmapped(UnmappedInstrument {
+            ticker: instrument.ticker.clone(),
+            isin: instrument.isin.clone(),
+            currency_code: instrument.currency_code.clone(),
+            exchange,
+            last_seen: get_current_date(DateType::DMY),
+        });
+    }
+
+    // For tickers Apollo only knows from positions or orders. The instruments list usually still
+    // has their details - only a ticker missing from it falls back to stripping the suffix
+    pub fn lookup_fh_symbol(ticker: &str) -> String {
+        let overrides: HashMap<String, String> = read_symbol_map(OVERRIDES_PATH);
+        if let Some(symbol) = overrides.get(ticker) {
+            return symbol.clone();
+        }
+
+        if let Some(symbol) = read_symbol_map(CACHE_PATH).get(ticker) {
+            return symbol.clone();
+        }
+
+        match get_listed_instrument(ticker) {
+            // resolve_fh_symbol has already recorded it as unmapped if it failed
+            Some(instrument) => {
+                if let Some(symbol) = resolve_fh_symbol(&instrument) {
+                    return symbol;
+                }
+            }
+            None => {
+                println!(
+                    "ST: {} is not in the instruments list. Guessing its Finnhub symbol.",
+                    ticker
+                );
+                record_unmapped(UnmappedInstrument {
+                    ticker: ticker.to_string(),
+                    isin: String::new(),
+                    currency_code: String::new(),
+                    exchange: None,
+                    last_seen: get_current_date(DateType::DMY),
+                });
+            }
+        }
+
+        convert_to_fh_ticker(&ticker.to_string())
+    }
+
+    pub fn resolve_fh_symbol(instrument: &Instrument) -> Option<String> {
+        let overrides: HashMap<String, String> = read_symbol_map(OVERRIDES_PATH);
+        if let Some(symbol) = overrides.get(&instrument.ticker) {
+            return Some(symbol.clone());
+        }
+
+        let mut cache: HashMap<String, String> = read_symbol_map(CACHE_PATH);
+        if let Some(symbol) = cache.get(&instrument.ticker) {
+            return Some(symbol.clone());
+        }
+
+        // Only pull in exchange names on a cache miss
+        let exchange_name: Option<String> = get_exchanges_from_file().and_then(|exchanges| {
+            ScheduleCalendar::new(&exchanges)
+                .get_exchange_name(instrument)
+                .cloned()
+        });
+
+        match derive_fh_symbol(instrument, exchange_name.as_deref()) {
+            Some(symbol) => {
+                cache.insert(instrument.ticker.clone(), symbol.clone());
+                write_json(CACHE_PATH, &cache);
+                Some(symbol)
+            }
+            None => {
+                report_unmapped(instrument, exchange_name);
+                None
+            }
+        }
+    }
+}
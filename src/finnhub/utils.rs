@@ -1,13 +1,23 @@
 pub mod helpers {
+    use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+    use lazy_static::lazy_static;
     use reqwest::{header, Body, Client, RequestBuilder, Response};
     use serde::{Deserialize, Serialize};
     use serde_json::Value;
     use std::env;
+    use std::num::NonZeroU32;
     use tokio;
 
-    use crate::helpers::helpers::helpers::sleep_thread;
+    use crate::FH_CALLS_PER_MINUTE;
 
-    #[derive(Debug, Serialize, Deserialize)]
+    lazy_static! {
+        // Shared by every thread so concurrent screening stays within the Finnhub plan's budget
+        static ref FH_RATE_LIMITER: DefaultDirectRateLimiter = RateLimiter::direct(Quota::per_minute(
+            NonZeroU32::new(*FH_CALLS_PER_MINUTE).unwrap_or(NonZeroU32::MIN)
+        ));
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct FHStockData {
         #[serde(rename = "c")]
         pub current_price: f64, // Current price
@@ -37,7 +47,7 @@ pub mod helpers {
         return fh_ticker.to_ascii_uppercase();
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct CompanyInfo {
         pub country: String,
         pub currency: String,
@@ -101,6 +111,7 @@ pub mod helpers {
 
         let client: Client = Client::new();
 
+        FH_RATE_LIMITER.until_ready().await;
         let request: RequestBuilder = client.get(&endpoint).header(header::AUTHORIZATION, api_key);
         let response: Response = request.send().await.unwrap();
 
@@ -145,6 +156,7 @@ pub mod helpers {
 
         let client: Client = Client::new();
 
+        FH_RATE_LIMITER.until_ready().await;
         let request: RequestBuilder = client.get(&endpoint).header(header::AUTHORIZATION, api_key);
         let res = request.send().await;
 
@@ -192,6 +204,7 @@ pub mod helpers {
 
         let client: Client = Client::new();
 
+        FH_RATE_LIMITER.until_ready().await;
         let request: RequestBuilder = client.get(&endpoint).header(header::AUTHORIZATION, api_key);
         let response: Response = request.send().await.unwrap();
 
@@ -225,6 +238,7 @@ pub mod helpers {
         endpoint = format!("{}&token={}", endpoint, api_key);
 
         let client: Client = Client::new();
+        FH_RATE_LIMITER.until_ready().await;
         let request: RequestBuilder = client.get(&endpoint).header(header::AUTHORIZATION, api_key);
         let res: Result<Response, reqwest::Error> = request.send().await;
 
@@ -234,10 +248,6 @@ pub mod helpers {
 
         let response: Response = res.unwrap();
 
-        // println!("Request sent. Throttling thread...");
-        sleep_thread(3);
-        // println!("Throttle complete.");
-
         if response.status().is_success() {
             let body: String = response.text().await.unwrap();
             let result: Result<Value, serde_json::Error> = serde_json::from_str(&body);
@@ -0,0 +1,71 @@
+pub mod exclusions {
+    use crate::file_control::cache::cache::load_company_cache;
+    use crate::finnhub::symbols::symbols::lookup_fh_symbol;
+    use crate::helpers::listings::listings::get_listed_instrument;
+    use crate::trading212::types::types::Instrument;
+    use crate::EXCLUSION_POLICY;
+
+    fn matches_any(value: &str, excluded: &Vec<&'static str>) -> bool {
+        excluded
+            .iter()
+            .any(|excluded| excluded.eq_ignore_ascii_case(value))
+    }
+
+    // Ticker patterns match anywhere in the ticker - "vusa" excludes VUSAl_EQ
+    fn get_ticker_pattern_reason(ticker: &String) -> Option<String> {
+        let ticker_lower: String = ticker.to_ascii_lowercase();
+        EXCLUSION_POLICY
+            .ticker_patterns
+            .iter()
+            .find(|pattern| ticker_lower.contains(&pattern.to_ascii_lowercase()))
+            .map(|pattern| format!("ticker matches excluded pattern \"{}\"", pattern))
+    }
+
+    // Reason the instrument is excluded, or None if it may be traded.
+    // industry is Finnhub's finnhubIndustry, when known
+    pub fn get_exclusion_reason(instrument: &Instrument, industry: Option<&str>) -> Option<String> {
+        if let Some(reason) = get_ticker_pattern_reason(&instrument.ticker) {
+            return Some(reason);
+        }
+        if matches_any(&instrument.isin, &EXCLUSION_POLICY.isins) {
+            return Some(format!("ISIN {} is excluded", instrument.isin));
+        }
+        if matches_any(&instrument.asset_type, &EXCLUSION_POLICY.asset_types) {
+            return Some(format!("asset type {} is excluded", instrument.asset_type));
+        }
+        if matches_any(&instrument.currency_code, &EXCLUSION_POLICY.currencies) {
+            return Some(format!("currency {} is excluded", instrument.currency_code));
+        }
+        if let Some(industry) = industry {
+            if matches_any(industry, &EXCLUSION_POLICY.industries) {
+                return Some(format!("industry {} is excluded", industry));
+            }
+        }
+        None
+    }
+
+    // For orders and positions, where only the ticker is to hand.
+    // Instrument details come from the last instruments list and the industry from the company cache
+    pub fn get_ticker_exclusion_reason(ticker: &String) -> Option<String> {
+        if let Some(reason) = get_ticker_pattern_reason(ticker) {
+            return Some(reason);
+        }
+
+        let instrument: Option<Instrument> = get_listed_instrument(ticker);
+
+        let industry: Option<String> = match EXCLUSION_POLICY.industries.is_empty() {
+            true => None,
+            false => load_company_cache(&lookup_fh_symbol(ticker))
+                .company_info
+                .map(|cached| cached.value.finnhub_industry),
+        };
+
+        match instrument {
+            Some(instrument) => get_exclusion_reason(&instrument, industry.as_deref()),
+            None => industry.and_then(|industry| {
+                matches_any(&industry, &EXCLUSION_POLICY.industries)
+                    .then(|| format!("industry {} is excluded", industry))
+            }),
+        }
+    }
+}
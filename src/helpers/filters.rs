@@ -1,65 +1,73 @@
 pub mod filtering {
-    use std::{thread, time::Duration};
+    use std::collections::HashMap;
+
+    use chrono::{Local, NaiveDate};
 
     use crate::{
-        helpers::types::types::FullCompanyInfo, trading212::types::types::Instrument,
-        FILTER_STRICTNESS, MINIMUM_MARKET_CAP,
+        helpers::peers::peers::PeerScore,
+        helpers::screening::screening::run_screening_job,
+        helpers::types::types::{EarningsRelease, FullCompanyInfo, SentimentData},
+        trading212::types::types::Instrument,
+        EARNINGS_BLACKOUT_DAYS, FILTER_STRICTNESS, INSIDER_SENTIMENT_MONTHS, MINIMUM_MARKET_CAP,
+        MINIMUM_MSPR, MINIMUM_NEWS_SENTIMENT, MINIMUM_PEER_PERCENTILE,
     };
 
-    // Could add in sentiment creation based on recent company news
+    // Passed instruments, best ranked first
     pub fn filter_trading212_instruments(full_stock_list: Vec<Instrument>) -> Vec<Instrument> {
-        let mut filtered_list: Vec<Instrument> = Vec::new();
-
-        for instrument in full_stock_list {
-            let raw_full_company_info: Option<FullCompanyInfo> =
-                get_full_company_info(instrument.clone());
-
-            let company_info: FullCompanyInfo = match raw_full_company_info {
-                Some(company_info) => company_info,
-                None => {
-                    println!("BT: Company info collection failed. Skipping...\n");
-                    thread::sleep(Duration::from_secs(5));
-                    continue;
-                }
-            };
-        }
-
-        filtered_list
+        let mut instruments: HashMap<String, Instrument> = full_stock_list
+            .iter()
+            .map(|instrument| (instrument.ticker.clone(), instrument.clone()))
+            .collect();
+
+        run_screening_job(full_stock_list)
+            .into_iter()
+            .filter(|result| result.passed)
+            .filter_map(|result| instruments.remove(&result.ticker))
+            .collect()
     }
 
     pub fn stock_passes_filters(company: &FullCompanyInfo) -> bool {
         println!("\nST: --- Applying filters...");
 
-        if !filter_market_cap(company) {
-            println!("ST: --- Filtering FAILED\n");
-            return false;
+        if get_filter_failures(company, true).is_empty() {
+            println!("ST: --- Filtering complete.\n");
+            return true;
         }
 
-        if !filter_volume(company) {
-            println!("ST: --- Filtering FAILED\n");
-            return false;
-        }
-        if !filter_volatility(company) {
-            println!("ST: --- Filtering FAILED\n");
-            return false;
-        }
-        if !filter_daily_price_performance(company) {
-            println!("ST: --- Filtering FAILED\n");
-            return false;
-        }
+        println!("ST: --- Filtering FAILED\n");
+        return false;
+    }
+
+    // Names of every filter the company fails. Stops at the first failure when short_circuit is set
+    pub fn get_filter_failures(company: &FullCompanyInfo, short_circuit: bool) -> Vec<String> {
+        let mut filters: Vec<(&str, fn(&FullCompanyInfo) -> bool)> = vec![
+            ("market_cap", filter_market_cap),
+            ("volume", filter_volume),
+            ("volatility", filter_volatility),
+            ("daily_price_performance", filter_daily_price_performance),
+            ("news_sentiment", filter_news_sentiment),
+            ("earnings_blackout", filter_earnings_blackout),
+            ("peer_ranking", filter_peer_ranking),
+        ];
 
         match *FILTER_STRICTNESS {
             crate::FILTERING_STRICTNESS::STRONG => {
-                if !filter_insider_activity(company) {
-                    println!("ST: --- Filtering FAILED\n");
-                    return false;
-                }
+                filters.push(("insider_activity", filter_insider_activity));
             }
             crate::FILTERING_STRICTNESS::LIGHT => {}
         };
 
-        println!("ST: --- Filtering complete.\n");
-        return true;
+        let mut failures: Vec<String> = Vec::new();
+        for (name, filter) in filters {
+            if !filter(company) {
+                failures.push(name.to_string());
+                if short_circuit {
+                    break;
+                }
+            }
+        }
+
+        failures
     }
 
     fn filter_market_cap(company: &FullCompanyInfo) -> bool {
@@ -91,6 +99,99 @@ pub mod filtering {
         return false;
     }
 
+    fn filter_news_sentiment(company: &FullCompanyInfo) -> bool {
+        if company.news_sentiment.articles.is_empty() {
+            println!("ST: PASSED - No recent news to score.");
+            return true;
+        }
+
+        if company.news_sentiment.score < *MINIMUM_NEWS_SENTIMENT {
+            println!(
+                "ST: FAILED - News sentiment of {} is below acceptable parameters.",
+                format!("{:.3}", company.news_sentiment.score)
+            );
+            return false;
+        }
+
+        println!(
+            "ST: PASSED - News sentiment of {} is within acceptable parameters.",
+            format!("{:.3}", company.news_sentiment.score)
+        );
+        return true;
+    }
+
+    // Day the market first trades on the release - after close releases land the next day
+    fn get_earnings_reaction_date(release: &EarningsRelease) -> Option<NaiveDate> {
+        let release_date: NaiveDate = NaiveDate::parse_from_str(&release.date, "%Y-%m-%d").ok()?;
+
+        match release.hour.to_ascii_lowercase().as_str() {
+            "amc" => Some(release_date + chrono::Duration::days(1)),
+            _ => Some(release_date), // bmo, dmh and unknown
+        }
+    }
+
+    pub fn get_days_until_earnings(releases: &Vec<EarningsRelease>) -> Option<i64> {
+        let today: NaiveDate = Local::now().date_naive();
+
+        releases
+            .iter()
+            .filter_map(get_earnings_reaction_date)
+            .map(|reaction_date| (reaction_date - today).num_days())
+            .filter(|days| *days >= 0)
+            .min()
+    }
+
+    pub fn is_within_earnings_blackout(releases: &Vec<EarningsRelease>) -> bool {
+        get_days_until_earnings(releases).is_some_and(|days| days <= *EARNINGS_BLACKOUT_DAYS)
+    }
+
+    fn filter_earnings_blackout(company: &FullCompanyInfo) -> bool {
+        let releases: &Vec<EarningsRelease> = match &company.company_earnings_calendar {
+            Some(releases) => releases,
+            None => {
+                println!("ST: PASSED - No upcoming earnings releases.");
+                return true;
+            }
+        };
+
+        if is_within_earnings_blackout(releases) {
+            println!(
+                "ST: FAILED - Earnings release in {} days is inside the blackout window.",
+                get_days_until_earnings(releases).unwrap_or(0)
+            );
+            return false;
+        }
+
+        println!("ST: PASSED - No earnings release inside the blackout window.");
+        return true;
+    }
+
+    fn filter_peer_ranking(company: &FullCompanyInfo) -> bool {
+        let peer_score: &PeerScore = match &company.peer_score {
+            Some(peer_score) => peer_score,
+            None => {
+                println!("ST: PASSED - No peer ranking available.");
+                return true;
+            }
+        };
+
+        if peer_score.score < *MINIMUM_PEER_PERCENTILE {
+            println!(
+                "ST: FAILED - Peer percentile of {} against {} peers is below acceptable parameters.",
+                format!("{:.3}", peer_score.score),
+                peer_score.peer_count
+            );
+            return false;
+        }
+
+        println!(
+            "ST: PASSED - Peer percentile of {} against {} peers is within acceptable parameters.",
+            format!("{:.3}", peer_score.score),
+            peer_score.peer_count
+        );
+        return true;
+    }
+
     fn filter_recent_price_performance(company: &FullCompanyInfo) -> bool {
         if company
             .company_financials
@@ -230,27 +331,101 @@ pub mod filtering {
     //     return true;
     // }
 
-    fn filter_insider_activity(company: &FullCompanyInfo) -> bool {
-        if company.insider_transactions.is_none() {
-            println!("ST: PASSED - No insider activity detected.");
-            return true;
+    // Most recent months first, limited to the configured window
+    fn get_recent_sentiment(company: &FullCompanyInfo) -> Vec<&SentimentData> {
+        let mut months: Vec<&SentimentData> = company.company_sentiment.iter().collect();
+        months.sort_by(|a, b| (b.year, b.month).cmp(&(a.year, a.month)));
+        months.truncate(*INSIDER_SENTIMENT_MONTHS as usize);
+        months
+    }
+
+    // Linear recency weighting - the latest month counts N times, the oldest once
+    fn get_mspr_trend(months: &Vec<&SentimentData>) -> Option<f64> {
+        if months.is_empty() {
+            return None;
         }
 
-        let total_change: i64 = company
+        let count: usize = months.len();
+        let (weighted_sum, weight_total) =
+            months
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(sum, total), (i, month)| {
+                    let weight: f64 = (count - i) as f64;
+                    (sum + month.mspr * weight, total + weight)
+                });
+
+        Some(weighted_sum / weight_total)
+    }
+
+    // Only open market trades show conviction - option exercises (M), awards (A),
+    // tax withholding (F) and gifts (G) happen regardless of an insider's view
+    fn get_open_market_net_shares(company: &FullCompanyInfo) -> i64 {
+        let cutoff: NaiveDate = Local::now().date_naive()
+            - chrono::Duration::days(31 * *INSIDER_SENTIMENT_MONTHS as i64);
+
+        company
             .insider_transactions
             .as_ref()
-            .unwrap()
-            .iter()
-            .map(|t| t.change)
-            .sum();
+            .map(|transactions| {
+                transactions
+                    .iter()
+                    .filter(|t| {
+                        NaiveDate::parse_from_str(&t.transaction_date, "%Y-%m-%d")
+                            .is_ok_and(|date| date >= cutoff)
+                    })
+                    .map(|t| match t.transaction_code.as_str() {
+                        "P" => t.change.abs(),
+                        "S" => -t.change.abs(),
+                        _ => 0,
+                    })
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
 
-        if total_change > 0 {
-            println!("ST: PASSED - Insider activity has strong buying trend.");
+    fn filter_insider_activity(company: &FullCompanyInfo) -> bool {
+        let open_market_net: i64 = get_open_market_net_shares(company);
+
+        // Insiders buying with their own cash outweighs the aggregate ratio
+        if open_market_net > 0 {
+            println!(
+                "ST: PASSED - Insiders bought {} shares on the open market.",
+                open_market_net
+            );
             return true;
         }
 
-        println!("ST: FAILED - Insider activity has strong selling trend.");
-        return false;
+        let mspr_trend: Option<f64> = get_mspr_trend(&get_recent_sentiment(company));
+
+        match mspr_trend {
+            None if open_market_net == 0 => {
+                println!("ST: PASSED - No insider activity detected.");
+                return true;
+            }
+            Some(mspr) if mspr >= *MINIMUM_MSPR && open_market_net == 0 => {
+                println!(
+                    "ST: PASSED - Insider MSPR trend of {} is within acceptable parameters.",
+                    format!("{:.3}", mspr)
+                );
+                return true;
+            }
+            Some(mspr) => {
+                println!(
+                    "ST: FAILED - Insider MSPR trend of {} with {} open market shares sold.",
+                    format!("{:.3}", mspr),
+                    open_market_net.abs()
+                );
+                return false;
+            }
+            None => {
+                println!(
+                    "ST: FAILED - Insiders sold {} shares on the open market.",
+                    open_market_net.abs()
+                );
+                return false;
+            }
+        }
     }
 
     // fn filter_short_interest(company: &FullCompanyInfo) -> bool {
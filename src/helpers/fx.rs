@@ -0,0 +1,173 @@
+pub mod fx {
+    use std::collections::HashMap;
+
+    use bigdecimal::{BigDecimal, One, Zero};
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    use crate::finnhub::utils::helpers::make_fh_request;
+    use crate::helpers::helpers::helpers::{
+        get_current_date, is_before_today, print_message, THREAD,
+    };
+    use crate::helpers::money::money::{decimal_from_f64, Money, Price, Quantity};
+    use crate::helpers::types::types::DateType;
+    use crate::{ACCOUNT_CURRENCY, FX_FEE_PERC, FX_PROVIDER, FX_RATE_PROVIDER};
+
+    const FX_CACHE_PATH: &str = "src/data/fx_rates.json";
+    // Hand written rates for offline runs and tests
+    const FX_LOCAL_PATH: &str = "src/data/fx_rates_local.json";
+
+    // 1 unit of base = rates[code] units of code
+    #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+    pub struct FxRates {
+        pub base: String,
+        #[serde(rename = "quote")]
+        pub rates: HashMap<String, f64>,
+        #[serde(default)]
+        pub creation_date: String,
+    }
+
+    impl FxRates {
+        fn units_per_base(&self, currency: &str) -> Option<BigDecimal> {
+            if currency == self.base {
+                return Some(BigDecimal::one());
+            }
+
+            let rate: BigDecimal = decimal_from_f64(*self.rates.get(currency)?);
+            if rate.is_zero() {
+                return None;
+            }
+            Some(rate)
+        }
+
+        pub fn convert(&self, amount: &BigDecimal, from: &str, to: &str) -> Option<BigDecimal> {
+            // Pence quoted LSE listings convert to pounds without needing a rate
+            let hundred: BigDecimal = BigDecimal::from(100);
+            let (amount, from): (BigDecimal, &str) = match from {
+                "GBX" => (amount / &hundred, "GBP"),
+                _ => (amount.clone(), from),
+            };
+            let (to, to_pence): (&str, bool) = match to {
+                "GBX" => ("GBP", true),
+                _ => (to, false),
+            };
+
+            let converted: BigDecimal = match from == to {
+                true => amount,
+                false => (amount / self.units_per_base(from)?) * self.units_per_base(to)?,
+            };
+
+            match to_pence {
+                true => Some(converted * hundred),
+                false => Some(converted),
+            }
+        }
+    }
+
+    fn fetch_finnhub_rates() -> Option<FxRates> {
+        print_message(THREAD::COLLECTION, "Fetching FX rates...");
+
+        let endpoint: String = format!(
+            "https://finnhub.io/api/v1/forex/rates?base={}",
+            account_base_currency()
+        );
+        let data: Option<Value> = make_fh_request(endpoint);
+
+        if data.is_none() {
+            println!("FX rate data was null.\n");
+            return None;
+        }
+
+        match serde_json::from_value::<FxRates>(data.unwrap()) {
+            Ok(mut rates) => {
+                rates.creation_date = get_current_date(DateType::DMY);
+                Some(rates)
+            }
+            Err(e) => {
+                println!("Error deserialising FX rates: {:?}\n", e);
+                None
+            }
+        }
+    }
+
+    fn read_rates_file(path: &str) -> Option<FxRates> {
+        let contents: String = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    // GBX accounts do not exist, but keep pence out of the Finnhub base just in case
+    fn account_base_currency() -> &'static str {
+        match *ACCOUNT_CURRENCY {
+            "GBX" => "GBP",
+            currency => currency,
+        }
+    }
+
+    pub fn load_fx_rates() -> Option<FxRates> {
+        match *FX_RATE_PROVIDER {
+            FX_PROVIDER::LOCAL_FILE => read_rates_file(FX_LOCAL_PATH),
+            FX_PROVIDER::FINNHUB => {
+                // Rates are refreshed once a day
+                if let Some(rates) = read_rates_file(FX_CACHE_PATH) {
+                    if !is_before_today(&rates.creation_date) {
+                        return Some(rates);
+                    }
+                }
+
+                let rates: FxRates = fetch_finnhub_rates()?;
+                let contents: String =
+                    serde_json::to_string_pretty(&rates).expect("FX rates serialization failed");
+                std::fs::create_dir_all("src/data").expect("Failed to create data directory.");
+                if std::fs::write(FX_CACHE_PATH, contents).is_err() {
+                    print_message(THREAD::FILE, "Failed to write FX rates to file");
+                }
+                Some(rates)
+            }
+        }
+    }
+
+    // Trading212 charges its FX fee on each leg of a trade in a foreign currency
+    pub fn get_fx_fee_rate(instrument_currency: &str) -> BigDecimal {
+        if is_account_currency(instrument_currency) {
+            return BigDecimal::zero();
+        }
+        decimal_from_f64(*FX_FEE_PERC) / BigDecimal::from(100)
+    }
+
+    pub fn is_account_currency(currency: &str) -> bool {
+        // GBX is just GBP quoted in pence, so no conversion takes place
+        match currency {
+            "GBX" => account_base_currency() == "GBP",
+            _ => currency == account_base_currency(),
+        }
+    }
+
+    pub fn price_in_account_currency(
+        price: &Price,
+        instrument_currency: &str,
+        rates: &FxRates,
+    ) -> Option<Price> {
+        rates
+            .convert(&price.0, instrument_currency, *ACCOUNT_CURRENCY)
+            .map(Price)
+    }
+
+    // Net profit in account currency of buying and selling at the given instrument currency prices
+    pub fn get_expected_profit(
+        buy_price: &Price,
+        sell_price: &Price,
+        quantity: &Quantity,
+        instrument_currency: &str,
+        rates: &FxRates,
+    ) -> Option<Money> {
+        let buy_price: Price = price_in_account_currency(buy_price, instrument_currency, rates)?;
+        let sell_price: Price = price_in_account_currency(sell_price, instrument_currency, rates)?;
+        let fee_rate: BigDecimal = get_fx_fee_rate(instrument_currency);
+
+        let cost: BigDecimal = &buy_price.0 * &quantity.0;
+        let proceeds: BigDecimal = &sell_price.0 * &quantity.0;
+        let fees: BigDecimal = (&cost + &proceeds) * fee_rate;
+
+        Some(Money(proceeds - cost - fees))
+    }
+}
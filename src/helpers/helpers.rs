@@ -1,26 +1,36 @@
 pub mod helpers {
 
+    use bigdecimal::{BigDecimal, RoundingMode};
     use chrono::{DateTime, Duration, Local, Month, NaiveDate, Utc};
     use rand::seq::SliceRandom;
     use rand::thread_rng;
-    use serde_json::Error;
     use serde_json::{self, Value};
     use std::time::Instant;
     use std::{fs, path::Path};
 
+    use crate::control::profiles::profiles::get_profile_strategy;
+    use crate::file_control::cache::cache::{
+        get_or_refresh, load_company_cache, save_company_cache, CachedCompanyInfo,
+    };
+    use crate::file_control::write::write::write_sentiment_audit;
     use crate::finnhub::utils::helpers::make_fh_request;
+    use crate::helpers::money::money::{decimal_from_f64, decimal_to_f64, Money};
+    use crate::helpers::peers::peers::{score_against_peers, PeerScore};
+    use crate::helpers::sentiment::sentiment::{score_company_news, NewsSentiment};
     use crate::helpers::types::types::{EarningsCalendar, FullInsiderTransaction, RawSentData};
     use crate::{
-        finnhub::utils::helpers::{
-            convert_to_fh_ticker, get_company_data, get_stock_price, CompanyInfo, FHStockData,
-        },
+        finnhub::symbols::symbols::resolve_fh_symbol,
+        finnhub::utils::helpers::{get_company_data, get_stock_price, CompanyInfo, FHStockData},
         helpers::types::types::{
             CompanyFinancials, DateType, EarningsRelease, FullCompanyInfo, InsiderTransaction,
             NewsArticle, SentimentData,
         },
         trading212::types::types::{BalanceObject, Instrument, Position},
     };
-    use crate::{AMOUNT_OF_TICKERS_TO_BUY, MINIMUM_MARKET_CAP, SPEND_PERC};
+    use crate::{
+        StrategyConfig, EARNINGS_LOOKAHEAD_DAYS, FINANCIALS_CACHE_TTL, INSIDER_SENTIMENT_MONTHS,
+        MINIMUM_MARKET_CAP, NEWS_CACHE_TTL, PEERS_CACHE_TTL, PROFILE_CACHE_TTL, QUOTE_CACHE_TTL,
+    };
 
     pub enum THREAD {
         COLLECTION,
@@ -67,22 +77,16 @@ pub mod helpers {
     }
 
     pub fn round_down(original_number: f64, decimals: u32) -> f64 {
-        let factor: f64 = 10f64.powi(decimals as i32);
-        let rounded: f64 = (original_number * factor).floor() / factor;
-        rounded
+        // Rounded in decimal to avoid float artifacts such as 1.15 -> 1.14
+        let rounded: BigDecimal = decimal_from_f64(original_number)
+            .with_scale_round(decimals as i64, RoundingMode::Floor);
+        decimal_to_f64(&rounded)
     }
 
     pub fn chop_two_after_dec(num: f64) -> f64 {
-        let num_string = format!("{:.10}", num); // Convert to string with a sufficient number of decimal places
-        let parts: Vec<&str> = num_string.split('.').collect(); // Split into integer and fractional parts
-        if parts.len() > 1 {
-            let truncated_fraction = &parts[1][..2.min(parts[1].len())]; // Take only the first two digits of the fractional part
-            format!("{}.{}", parts[0], truncated_fraction)
-                .parse()
-                .unwrap() // Concatenate and convert back to f64
-        } else {
-            num
-        }
+        // Truncates towards zero, keeping only the first two digits of the fractional part
+        let chopped: BigDecimal = decimal_from_f64(num).with_scale_round(2, RoundingMode::Down);
+        decimal_to_f64(&chopped)
     }
 
     pub fn calc_perc_change(bought_at: f64, current: f64) -> f64 {
@@ -126,11 +130,15 @@ pub mod helpers {
         return positions;
     }
 
-    pub fn calculate_amount_spent_per_ticker(balance_data: &BalanceObject) -> f64 {
-        let available_for_trading: f64 = balance_data.free * (1.0 - (SPEND_PERC.clamp(0.0, 1.0)));
+    pub fn calculate_amount_spent_per_ticker(balance_data: &BalanceObject) -> Money {
+        let free: BigDecimal = decimal_from_f64(balance_data.free);
+        let strategy: &StrategyConfig = get_profile_strategy();
+        let spend_ratio: BigDecimal = decimal_from_f64(1.0 - (strategy.spend_perc.clamp(0.0, 1.0)));
+        let available_for_trading: BigDecimal = free * spend_ratio;
 
-        let amount_per_ticker: f64 = available_for_trading / *AMOUNT_OF_TICKERS_TO_BUY as f64;
-        println!("ST: Spending {:?} per ticker.\n", amount_per_ticker);
+        let amount_per_ticker: Money =
+            Money(available_for_trading / BigDecimal::from(strategy.tickers_to_buy as u64));
+        println!("ST: Spending {} per ticker.\n", amount_per_ticker);
 
         amount_per_ticker
     }
@@ -164,67 +172,119 @@ pub mod helpers {
     }
 
     pub fn get_full_company_info(instrument: Instrument) -> Option<FullCompanyInfo> {
-        let fh_ticker: &String = &convert_to_fh_ticker(&instrument.ticker);
+        let fh_ticker: &String = &match resolve_fh_symbol(&instrument) {
+            Some(fh_ticker) => fh_ticker,
+            None => return None,
+        };
 
         println!("\nST: --- Starting info collection for {}...", fh_ticker);
 
-        let company_info: CompanyInfo = match get_company_data(fh_ticker) {
-            Some(raw_company_info) => match raw_company_info {
-                Ok(company_info) => company_info,
-                Err(e) => return None,
-            },
-            None => return None,
-        };
+        // Only fields older than their TTL are fetched from Finnhub
+        let mut cache: CachedCompanyInfo = load_company_cache(fh_ticker);
+
+        let company_info: CompanyInfo =
+            match get_or_refresh(&mut cache.company_info, *PROFILE_CACHE_TTL, || {
+                get_company_data(fh_ticker)?.ok()
+            }) {
+                Some(company_info) => company_info,
+                None => return None,
+            };
 
         if company_info.market_capitalization < *MINIMUM_MARKET_CAP {
             println!(
                 "ST: Market Cap of {}m is below acceptable parameters.",
                 company_info.market_capitalization.trunc() as i64
             );
+            save_company_cache(fh_ticker, &cache);
             return None;
         }
 
-        let company_quote_res: Result<FHStockData, Error> = match get_stock_price(fh_ticker) {
-            None => return None,
-            Some(quote_res) => quote_res,
-        };
-
-        let company_quote: FHStockData = match company_quote_res {
-            Err(e) => {
-                println!("ST: Error fetching quote: {:?}", e);
+        let company_quote: FHStockData = match get_or_refresh(
+            &mut cache.quote,
+            *QUOTE_CACHE_TTL,
+            || match get_stock_price(fh_ticker)? {
+                Ok(company_quote) => Some(company_quote),
+                Err(e) => {
+                    println!("ST: Error fetching quote: {:?}", e);
+                    None
+                }
+            },
+        ) {
+            Some(company_quote) => company_quote,
+            None => {
+                save_company_cache(fh_ticker, &cache);
                 return None;
             }
-            Ok(company_quote) => company_quote,
         };
 
-        let insider_data: Option<Vec<InsiderTransaction>> = get_insider_transactions(fh_ticker);
+        let insider_data: Option<Vec<InsiderTransaction>> = get_or_refresh(
+            &mut cache.insider_transactions,
+            *FINANCIALS_CACHE_TTL,
+            || get_insider_transactions(fh_ticker),
+        );
+
+        let news_data: Vec<NewsArticle> =
+            match get_or_refresh(&mut cache.news, *NEWS_CACHE_TTL, || {
+                get_news_articles(fh_ticker)
+            }) {
+                Some(news_data) => news_data,
+                None => {
+                    save_company_cache(fh_ticker, &cache);
+                    return None;
+                }
+            };
 
-        let news_data: Vec<NewsArticle> = match get_news_articles(fh_ticker) {
-            Some(news_data) => news_data,
-            None => return None,
-        };
+        let news_sentiment: NewsSentiment = score_company_news(&news_data);
+        println!("ST: News sentiment score: {:.3}", news_sentiment.score);
+        write_sentiment_audit(&instrument.ticker, &news_sentiment);
+
+        let peers_data: Option<Vec<String>> =
+            get_or_refresh(&mut cache.peers, *PEERS_CACHE_TTL, || {
+                get_company_peers(fh_ticker)
+            });
+
+        let financials_data: CompanyFinancials =
+            match get_or_refresh(&mut cache.financials, *FINANCIALS_CACHE_TTL, || {
+                get_company_financials(fh_ticker)
+            }) {
+                Some(financials_data) => financials_data,
+                None => {
+                    save_company_cache(fh_ticker, &cache);
+                    return None;
+                }
+            };
 
-        let peers_data: Option<Vec<String>> = get_company_peers(fh_ticker);
+        let sentiment_data: Vec<SentimentData> =
+            match get_or_refresh(&mut cache.sentiment, *FINANCIALS_CACHE_TTL, || {
+                get_company_sentiment(fh_ticker)
+            }) {
+                Some(sentiment_data) => sentiment_data,
+                None => {
+                    save_company_cache(fh_ticker, &cache);
+                    return None;
+                }
+            };
 
-        let financials_data: CompanyFinancials = match get_company_financials(fh_ticker) {
-            Some(financials_data) => financials_data,
-            None => return None,
-        };
+        let earnings_calendar: Option<Vec<EarningsRelease>> =
+            get_or_refresh(&mut cache.earnings_calendar, *FINANCIALS_CACHE_TTL, || {
+                get_earnings_calendar(fh_ticker)
+            });
 
-        let sentiment_data: Vec<SentimentData> = match get_company_sentiment(fh_ticker) {
-            Some(sentiment_data) => sentiment_data,
-            None => return None,
-        };
+        save_company_cache(fh_ticker, &cache);
 
-        let earnings_calendar: Option<Vec<EarningsRelease>> = get_earnings_calendar(fh_ticker);
+        // Peers are cached under their own symbols, so this runs after the candidate's cache is saved
+        let peer_score: Option<PeerScore> =
+            score_against_peers(fh_ticker, &financials_data, &peers_data);
 
         let full_company_data: FullCompanyInfo = FullCompanyInfo {
             instrument,
             company_info,
             company_stock_quote: company_quote,
             company_news: news_data,
+            news_sentiment,
             company_peers: peers_data,
             company_financials: financials_data,
+            peer_score,
             company_sentiment: sentiment_data,
             company_earnings_calendar: earnings_calendar,
             insider_transactions: insider_data,
@@ -341,10 +401,49 @@ pub mod helpers {
         }
     }
 
+    // Peers are shared across candidates, so their financials go through the same cache
+    pub fn get_cached_company_financials(fh_ticker: &String) -> Option<CompanyFinancials> {
+        let mut cache: CachedCompanyInfo = load_company_cache(fh_ticker);
+        let financials: Option<CompanyFinancials> =
+            get_or_refresh(&mut cache.financials, *FINANCIALS_CACHE_TTL, || {
+                get_company_financials(fh_ticker)
+            });
+        save_company_cache(fh_ticker, &cache);
+        financials
+    }
+
+    pub fn get_cached_quote(fh_ticker: &String) -> Option<FHStockData> {
+        let mut cache: CachedCompanyInfo = load_company_cache(fh_ticker);
+        let quote: Option<FHStockData> =
+            get_or_refresh(
+                &mut cache.quote,
+                *QUOTE_CACHE_TTL,
+                || match get_stock_price(fh_ticker)? {
+                    Ok(quote) => Some(quote),
+                    Err(e) => {
+                        println!("Error fetching quote: {:?}", e);
+                        None
+                    }
+                },
+            );
+        save_company_cache(fh_ticker, &cache);
+        quote
+    }
+
+    // The sell thread checks held positions every cycle, so it shares screening's cached calendar
+    pub fn get_cached_earnings_calendar(fh_ticker: &String) -> Option<Vec<EarningsRelease>> {
+        let mut cache: CachedCompanyInfo = load_company_cache(fh_ticker);
+        let calendar: Option<Vec<EarningsRelease>> =
+            get_or_refresh(&mut cache.earnings_calendar, *FINANCIALS_CACHE_TTL, || {
+                get_earnings_calendar(fh_ticker)
+            });
+        save_company_cache(fh_ticker, &cache);
+        calendar
+    }
+
     fn get_company_sentiment(fh_ticker: &String) -> Option<Vec<SentimentData>> {
         let today = Utc::now();
-        // 62 dats = 2 months?
-        let start_date = today - Duration::days(62);
+        let start_date = today - Duration::days(31 * *INSIDER_SENTIMENT_MONTHS as i64);
         let start_date_str = start_date.format("%Y-%m-%d").to_string();
         let end_date_str = today.format("%Y-%m-%d").to_string();
         let final_string = format!("{}&to={}", start_date_str, end_date_str);
@@ -376,14 +475,15 @@ pub mod helpers {
         Some(raw_data.data)
     }
 
-    fn get_earnings_calendar(fh_ticker: &String) -> Option<Vec<EarningsRelease>> {
+    pub fn get_earnings_calendar(fh_ticker: &String) -> Option<Vec<EarningsRelease>> {
         println!("ST: Fetching company earnings calendar...");
 
         let today = Utc::now();
-        // from the past year
+        // from the past year, plus upcoming releases for the earnings blackout
         let start_date = today - Duration::days(365);
+        let end_date = today + Duration::days(*EARNINGS_LOOKAHEAD_DAYS);
         let start_date_str = start_date.format("%Y-%m-%d").to_string();
-        let end_date_str = today.format("%Y-%m-%d").to_string();
+        let end_date_str = end_date.format("%Y-%m-%d").to_string();
         let final_string = format!("{}&to={}", start_date_str, end_date_str);
 
         println!(
@@ -0,0 +1,140 @@
+pub mod listings {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+
+    use lazy_static::lazy_static;
+
+    use crate::file_control::read::read::{
+        get_instrument_diffs_from_file, get_previous_instruments_from_file,
+    };
+    use crate::file_control::types::types::{InstrumentChange, InstrumentDiff};
+    use crate::helpers::helpers::helpers::{get_current_date, print_message, THREAD};
+    use crate::helpers::types::types::DateType;
+    use crate::trading212::types::types::Instrument;
+
+    const INSTRUMENTS_PATH: &str = "src/data/instruments.json";
+
+    lazy_static! {
+        // Upper case ticker -> instrument, rebuilt only when the instruments file changes
+        static ref INSTRUMENT_INDEX: Mutex<(Option<SystemTime>, HashMap<String, Instrument>)> =
+            Mutex::new((None, HashMap::new()));
+    }
+
+    fn compare_quantity(
+        changes: &mut Vec<InstrumentChange>,
+        ticker: &String,
+        field: &str,
+        previous: f64,
+        current: f64,
+    ) {
+        if previous != current {
+            changes.push(InstrumentChange {
+                ticker: ticker.clone(),
+                field: field.to_string(),
+                previous,
+                current,
+            });
+        }
+    }
+
+    pub fn diff_instruments(
+        previous_date: String,
+        previous: &Vec<Instrument>,
+        current: &Vec<Instrument>,
+    ) -> InstrumentDiff {
+        let previous_by_ticker: HashMap<&String, &Instrument> = previous
+            .iter()
+            .map(|instrument| (&instrument.ticker, instrument))
+            .collect();
+        let current_by_ticker: HashMap<&String, &Instrument> = current
+            .iter()
+            .map(|instrument| (&instrument.ticker, instrument))
+            .collect();
+
+        let mut added: Vec<Instrument> = Vec::new();
+        let mut changed: Vec<InstrumentChange> = Vec::new();
+
+        for instrument in current {
+            match previous_by_ticker.get(&instrument.ticker) {
+                None => added.push(instrument.clone()),
+                Some(old) => {
+                    compare_quantity(
+                        &mut changed,
+                        &instrument.ticker,
+                        "max_open_quantity",
+                        old.max_open_quantity,
+                        instrument.max_open_quantity,
+                    );
+                    compare_quantity(
+                        &mut changed,
+                        &instrument.ticker,
+                        "min_trade_quantity",
+                        old.min_trade_quantity,
+                        instrument.min_trade_quantity,
+                    );
+                }
+            }
+        }
+
+        let delisted: Vec<Instrument> = previous
+            .iter()
+            .filter(|instrument| !current_by_ticker.contains_key(&instrument.ticker))
+            .cloned()
+            .collect();
+
+        // addedOn is an ISO 8601 timestamp so it sorts as a string
+        added.sort_by(|a, b| b.added_on.cmp(&a.added_on));
+
+        print_message(
+            THREAD::COLLECTION,
+            &format!(
+                "Instrument diff: {} added, {} delisted, {} changed.",
+                added.len(),
+                delisted.len(),
+                changed.len()
+            ),
+        );
+
+        InstrumentDiff {
+            creation_date: get_current_date(DateType::DMY),
+            previous_date,
+            added,
+            delisted,
+            changed,
+        }
+    }
+
+    // Instrument details for a ticker from the last instruments list, however old
+    pub fn get_listed_instrument(ticker: &str) -> Option<Instrument> {
+        let modified: Option<SystemTime> = std::fs::metadata(INSTRUMENTS_PATH)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        let mut index = INSTRUMENT_INDEX.lock().unwrap();
+        if modified.is_some() && index.0 != modified {
+            index.1 = get_previous_instruments_from_file()
+                .map(|(_, instruments)| instruments)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|instrument| (instrument.ticker.to_ascii_uppercase(), instrument))
+                .collect();
+            index.0 = modified;
+        }
+
+        index.1.get(&ticker.to_ascii_uppercase()).cloned()
+    }
+
+    // Tickers first listed in today's diff
+    pub fn get_todays_new_listings() -> Vec<String> {
+        let today: String = get_current_date(DateType::DMY);
+
+        get_instrument_diffs_from_file()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|diff| diff.creation_date == today)
+            .flat_map(|diff| diff.added)
+            .map(|instrument| instrument.ticker)
+            .collect()
+    }
+}
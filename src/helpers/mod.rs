@@ -1,3 +1,12 @@
+pub mod exclusions;
 pub mod filters;
+pub mod fx;
 pub mod helpers;
+pub mod listings;
+pub mod money;
+pub mod peers;
+pub mod screening;
+pub mod sentiment;
+pub mod sizing;
 pub mod types;
+pub mod watchlists;
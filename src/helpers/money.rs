@@ -0,0 +1,272 @@
+pub mod money {
+    use std::fmt;
+    use std::str::FromStr;
+
+    use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive, Zero};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::trading212::types::types::Instrument;
+    use crate::{DEFAULT_TICK_SIZE, TICK_SIZE_OVERRIDES};
+
+    // Decimal backed price of a single share
+    #[derive(Debug, Clone, PartialEq, PartialOrd, Default)]
+    pub struct Price(pub BigDecimal);
+
+    // Decimal backed amount of shares. Negative for sell orders
+    #[derive(Debug, Clone, PartialEq, PartialOrd, Default)]
+    pub struct Quantity(pub BigDecimal);
+
+    // Decimal backed amount of account cash
+    #[derive(Debug, Clone, PartialEq, PartialOrd, Default)]
+    pub struct Money(pub BigDecimal);
+
+    // Going through the shortest string form avoids binary artifacts (0.1 -> 0.1000000000000000055...)
+    pub fn decimal_from_f64(value: f64) -> BigDecimal {
+        if !value.is_finite() {
+            return BigDecimal::zero();
+        }
+        BigDecimal::from_str(&value.to_string()).unwrap_or_default()
+    }
+
+    pub fn decimal_to_f64(value: &BigDecimal) -> f64 {
+        value.to_f64().unwrap_or(0.0)
+    }
+
+    // Rounds to the nearest multiple of the step in the given direction
+    pub fn round_to_step(value: &BigDecimal, step: &BigDecimal, mode: RoundingMode) -> BigDecimal {
+        if step.is_zero() {
+            return value.clone();
+        }
+        let steps: BigDecimal = (value / step).with_scale_round(0, mode);
+        (steps * step).normalized()
+    }
+
+    pub fn tick_size(instrument: &Instrument) -> BigDecimal {
+        // Trading212 does not expose tick sizes. Overrides match the ticker before the currency
+        let tick: f64 = TICK_SIZE_OVERRIDES
+            .iter()
+            .find(|(key, _)| instrument.ticker.eq_ignore_ascii_case(key))
+            .or_else(|| {
+                TICK_SIZE_OVERRIDES
+                    .iter()
+                    .find(|(key, _)| instrument.currency_code == *key)
+            })
+            .map(|(_, tick)| *tick)
+            .unwrap_or(*DEFAULT_TICK_SIZE);
+        decimal_from_f64(tick)
+    }
+
+    pub fn quantity_precision(instrument: &Instrument) -> i64 {
+        // Min trade quantity of 0.01 -> 2 decimals, 1.0 -> whole shares
+        decimal_from_f64(instrument.min_trade_quantity)
+            .normalized()
+            .fractional_digit_count()
+            .max(0)
+    }
+
+    impl Price {
+        pub fn from_f64(value: f64) -> Price {
+            Price(decimal_from_f64(value))
+        }
+
+        pub fn to_f64(&self) -> f64 {
+            decimal_to_f64(&self.0)
+        }
+
+        pub fn round_to_tick(&self, tick: &BigDecimal, mode: RoundingMode) -> Price {
+            Price(round_to_step(&self.0, tick, mode))
+        }
+
+        pub fn is_zero(&self) -> bool {
+            self.0.is_zero()
+        }
+    }
+
+    impl Quantity {
+        pub fn from_f64(value: f64) -> Quantity {
+            Quantity(decimal_from_f64(value))
+        }
+
+        pub fn to_f64(&self) -> f64 {
+            decimal_to_f64(&self.0)
+        }
+
+        // Always rounds towards zero so an order never exceeds what is held or affordable
+        pub fn round_to_precision(&self, decimals: i64) -> Quantity {
+            Quantity(
+                self.0
+                    .with_scale_round(decimals, RoundingMode::Down)
+                    .normalized(),
+            )
+        }
+
+        pub fn abs(&self) -> Quantity {
+            Quantity(self.0.abs())
+        }
+
+        pub fn negate(&self) -> Quantity {
+            Quantity(-self.0.clone())
+        }
+
+        pub fn is_buy(&self) -> bool {
+            self.0 > BigDecimal::zero()
+        }
+
+        pub fn is_zero(&self) -> bool {
+            self.0.is_zero()
+        }
+
+        pub fn min(self, other: Quantity) -> Quantity {
+            if other < self {
+                return other;
+            }
+            self
+        }
+    }
+
+    impl Money {
+        pub fn from_f64(value: f64) -> Money {
+            Money(decimal_from_f64(value))
+        }
+
+        pub fn to_f64(&self) -> f64 {
+            decimal_to_f64(&self.0)
+        }
+
+        // Amount of shares this much cash buys at the given price
+        pub fn quantity_at(&self, price: &Price) -> Quantity {
+            if price.is_zero() {
+                return Quantity::default();
+            }
+            Quantity(&self.0 / &price.0)
+        }
+    }
+
+    impl fmt::Display for Price {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0.normalized())
+        }
+    }
+
+    impl fmt::Display for Quantity {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0.normalized())
+        }
+    }
+
+    impl fmt::Display for Money {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0.with_scale_round(2, RoundingMode::HalfEven))
+        }
+    }
+
+    // Trading212 sends and expects plain JSON numbers
+    macro_rules! impl_number_serde {
+        ($name:ident) => {
+            impl Serialize for $name {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_f64(self.to_f64())
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let value: f64 = Deserialize::deserialize(deserializer)?;
+                    Ok($name::from_f64(value))
+                }
+            }
+        };
+    }
+
+    impl_number_serde!(Price);
+    impl_number_serde!(Quantity);
+    impl_number_serde!(Money);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn instrument(min_trade_quantity: f64) -> Instrument {
+            Instrument {
+                added_on: String::new(),
+                currency_code: String::from("USD"),
+                isin: String::new(),
+                max_open_quantity: 1000.0,
+                min_trade_quantity,
+                name: String::new(),
+                shortname: None,
+                ticker: String::from("AAPL_US_EQ"),
+                asset_type: String::from("STOCK"),
+                working_schedule_id: 0,
+            }
+        }
+
+        fn price(value: &str) -> Price {
+            Price(BigDecimal::from_str(value).unwrap())
+        }
+
+        fn quantity(value: &str) -> Quantity {
+            Quantity(BigDecimal::from_str(value).unwrap())
+        }
+
+        #[test]
+        fn buys_round_down_and_sells_round_up_to_the_tick() {
+            let tick: BigDecimal = BigDecimal::from_str("0.01").unwrap();
+
+            assert_eq!(
+                price("101.237").round_to_tick(&tick, RoundingMode::Down),
+                price("101.23")
+            );
+            assert_eq!(
+                price("101.231").round_to_tick(&tick, RoundingMode::Up),
+                price("101.24")
+            );
+        }
+
+        #[test]
+        fn prices_on_the_tick_are_left_alone() {
+            let tick: BigDecimal = BigDecimal::from_str("0.05").unwrap();
+
+            assert_eq!(
+                price("12.35").round_to_tick(&tick, RoundingMode::Down),
+                price("12.35")
+            );
+            assert_eq!(
+                price("12.35").round_to_tick(&tick, RoundingMode::Up),
+                price("12.35")
+            );
+            assert_eq!(
+                price("12.36").round_to_tick(&tick, RoundingMode::Up),
+                price("12.4")
+            );
+        }
+
+        #[test]
+        fn zero_tick_keeps_the_price() {
+            assert_eq!(
+                price("3.14159").round_to_tick(&BigDecimal::zero(), RoundingMode::Down),
+                price("3.14159")
+            );
+        }
+
+        #[test]
+        fn quantities_round_towards_zero() {
+            assert_eq!(quantity("1.239").round_to_precision(2), quantity("1.23"));
+            assert_eq!(quantity("-1.239").round_to_precision(2), quantity("-1.23"));
+            assert_eq!(quantity("7.99").round_to_precision(0), quantity("7"));
+        }
+
+        #[test]
+        fn precision_follows_min_trade_quantity() {
+            assert_eq!(quantity_precision(&instrument(0.01)), 2);
+            assert_eq!(quantity_precision(&instrument(0.001)), 3);
+            assert_eq!(quantity_precision(&instrument(1.0)), 0);
+        }
+
+        #[test]
+        fn f64_conversion_avoids_binary_artifacts() {
+            assert_eq!(decimal_from_f64(0.1), BigDecimal::from_str("0.1").unwrap());
+            assert_eq!(decimal_from_f64(f64::NAN), BigDecimal::zero());
+        }
+    }
+}
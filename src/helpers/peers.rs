@@ -0,0 +1,125 @@
+pub mod peers {
+    use serde::{Deserialize, Serialize};
+
+    use crate::helpers::helpers::helpers::get_cached_company_financials;
+    use crate::helpers::types::types::{CompanyFinancials, Metric};
+    use crate::PEER_COMPARISON_SIZE;
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct PeerScore {
+        pub peer_count: usize,
+        pub valuation_percentile: Option<f64>, // Share of peers with a higher P/E
+        pub margin_percentile: Option<f64>,    // Share of peers with a lower net margin
+        pub return_percentile: Option<f64>,    // Share of peers with a lower 52 week return
+        pub score: f64,                        // Mean of the available percentiles - 0.0 to 1.0
+    }
+
+    // Fraction of peers the candidate beats. Ties count as half a win
+    fn get_percentile(
+        candidate: f64,
+        peer_values: &Vec<f64>,
+        higher_is_better: bool,
+    ) -> Option<f64> {
+        if peer_values.is_empty() {
+            return None;
+        }
+
+        let wins: f64 = peer_values
+            .iter()
+            .map(|peer| {
+                let beats: bool = match higher_is_better {
+                    true => candidate > *peer,
+                    false => candidate < *peer,
+                };
+                match (beats, candidate == *peer) {
+                    (true, _) => 1.0,
+                    (false, true) => 0.5,
+                    (false, false) => 0.0,
+                }
+            })
+            .sum();
+
+        Some(wins / peer_values.len() as f64)
+    }
+
+    fn rank_metric(
+        candidate: &Metric,
+        peers: &Vec<CompanyFinancials>,
+        extract: fn(&Metric) -> Option<f64>,
+        higher_is_better: bool,
+    ) -> Option<f64> {
+        let candidate_value: f64 = extract(candidate)?;
+        let peer_values: Vec<f64> = peers
+            .iter()
+            .filter_map(|peer| extract(&peer.metric))
+            .collect();
+
+        get_percentile(candidate_value, &peer_values, higher_is_better)
+    }
+
+    // Loss making companies have no meaningful P/E so they are left out of the valuation ranking
+    fn positive_pe(metric: &Metric) -> Option<f64> {
+        metric.pe_ttm.filter(|pe| *pe > 0.0)
+    }
+
+    fn net_margin(metric: &Metric) -> Option<f64> {
+        metric.net_profit_margin_ttm
+    }
+
+    fn year_return(metric: &Metric) -> Option<f64> {
+        metric.week_price_return_daily_52
+    }
+
+    pub fn score_against_peers(
+        fh_ticker: &String,
+        financials: &CompanyFinancials,
+        peers: &Option<Vec<String>>,
+    ) -> Option<PeerScore> {
+        if *PEER_COMPARISON_SIZE == 0 {
+            return None;
+        }
+
+        let peer_tickers: Vec<&String> = peers
+            .as_ref()?
+            .iter()
+            .filter(|peer| !peer.eq_ignore_ascii_case(fh_ticker))
+            .take(*PEER_COMPARISON_SIZE)
+            .collect();
+
+        println!("ST: Comparing against {} peers...", peer_tickers.len());
+
+        let peer_financials: Vec<CompanyFinancials> = peer_tickers
+            .into_iter()
+            .filter_map(|peer| get_cached_company_financials(peer))
+            .collect();
+
+        if peer_financials.is_empty() {
+            println!("ST: No peer financials available.");
+            return None;
+        }
+
+        let valuation_percentile: Option<f64> =
+            rank_metric(&financials.metric, &peer_financials, positive_pe, false);
+        let margin_percentile: Option<f64> =
+            rank_metric(&financials.metric, &peer_financials, net_margin, true);
+        let return_percentile: Option<f64> =
+            rank_metric(&financials.metric, &peer_financials, year_return, true);
+
+        let available: Vec<f64> = [valuation_percentile, margin_percentile, return_percentile]
+            .into_iter()
+            .flatten()
+            .collect();
+
+        if available.is_empty() {
+            return None;
+        }
+
+        Some(PeerScore {
+            peer_count: peer_financials.len(),
+            valuation_percentile,
+            margin_percentile,
+            return_percentile,
+            score: available.iter().sum::<f64>() / available.len() as f64,
+        })
+    }
+}
@@ -0,0 +1,298 @@
+pub mod screening {
+    use std::collections::{HashSet, VecDeque};
+    use std::fs::{File, OpenOptions};
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, JoinHandle};
+
+    use chrono::Utc;
+    use serde::{Deserialize, Serialize};
+
+    use crate::file_control::write::write::write_screening_results_to_file;
+    use crate::finnhub::utils::helpers::FHStockData;
+    use crate::helpers::exclusions::exclusions::get_exclusion_reason;
+    use crate::helpers::filters::filtering::get_filter_failures;
+    use crate::helpers::helpers::helpers::{
+        get_current_date, get_full_company_info, is_before_today, print_message, THREAD,
+    };
+    use crate::helpers::listings::listings::get_todays_new_listings;
+    use crate::helpers::types::types::{DateType, FullCompanyInfo};
+    use crate::helpers::watchlists::watchlists::get_watchlist_tickers;
+    use crate::trading212::types::types::Instrument;
+    use crate::{
+        SCREENING_WORKERS, SCREEN_NEW_LISTINGS_FIRST, WATCHLIST_SCREENING, WATCHLIST_SCREENING_MODE,
+    };
+
+    // One JSON line per screened instrument, so a restart resumes the run from here
+    const PROGRESS_PATH: &str = "src/data/screening_progress.jsonl";
+
+    // Date the last full run finished. Its progress is kept until the day is out so
+    // reruns on the same day cost nothing
+    const COMPLETED_PATH: &str = "src/data/screening_completed.txt";
+
+    // Partial results are published for the buy thread every this many instruments
+    const PUBLISH_EVERY: usize = 100;
+
+    // Score weights - peer ranking carries the most signal, daily momentum the least
+    const PEER_WEIGHT: f64 = 0.5;
+    const SENTIMENT_WEIGHT: f64 = 0.3;
+    const MOMENTUM_WEIGHT: f64 = 0.2;
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct ScreeningResult {
+        pub ticker: String, // Trading212 ticker - full details are in instruments.json
+        pub fh_ticker: Option<String>,
+        pub passed: bool,
+        pub score: f64, // 0.0 to 1.0 - only meaningful for instruments with company data
+        pub failure_reasons: Vec<String>,
+        pub screened_at: i64,
+    }
+
+    // Results of today's finished run, or of the unfinished one whenever it started. A pass over the whole universe can
+    // outlast a day, and the buy thread rechecks every candidate against a fresh quote anyway
+    fn load_progress() -> Vec<ScreeningResult> {
+        match std::fs::read_to_string(PROGRESS_PATH).ok() {
+            Some(contents) => contents
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn append_progress(progress: &Mutex<Option<File>>, result: &ScreeningResult) {
+        let line: String =
+            serde_json::to_string(result).expect("Screening result serialization failed");
+
+        if let Some(file) = progress.lock().unwrap().as_mut() {
+            if writeln!(file, "{}", line).is_err() {
+                print_message(THREAD::FILE, "Failed to append screening progress");
+            }
+        }
+    }
+
+    // Passed first, then best score first
+    fn sort_results(results: &mut Vec<ScreeningResult>) {
+        results.sort_by(|a, b| {
+            b.passed.cmp(&a.passed).then(
+                b.score
+                    .partial_cmp(&a.score)
+                    .unwrap_or(std::cmp::Ordering::Equal),
+            )
+        });
+    }
+
+    // Weighted blend of peer ranking, news sentiment and today's price move
+    pub fn get_candidate_score(company: &FullCompanyInfo) -> f64 {
+        let peer: f64 = company
+            .peer_score
+            .as_ref()
+            .map(|peer_score| peer_score.score)
+            .unwrap_or(0.5);
+
+        // -1.0..1.0 -> 0.0..1.0
+        let sentiment: f64 = ((company.news_sentiment.score + 1.0) / 2.0).clamp(0.0, 1.0);
+
+        // -5% or worse scores 0.0, +5% or better scores 1.0
+        let quote: &FHStockData = &company.company_stock_quote;
+        let momentum: f64 = match quote.previous_close > 0.0 {
+            true => {
+                let change_perc: f64 =
+                    (quote.current_price - quote.previous_close) / quote.previous_close * 100.0;
+                ((change_perc + 5.0) / 10.0).clamp(0.0, 1.0)
+            }
+            false => 0.5,
+        };
+
+        peer * PEER_WEIGHT + sentiment * SENTIMENT_WEIGHT + momentum * MOMENTUM_WEIGHT
+    }
+
+    fn screen_instrument(instrument: Instrument, bypass_filters: bool) -> ScreeningResult {
+        let screened_at: i64 = Utc::now().timestamp();
+
+        // Excluded instruments are not worth the API calls
+        if let Some(reason) = get_exclusion_reason(&instrument, None) {
+            println!("ST: Skipping {} - {}.", instrument.ticker, reason);
+            return ScreeningResult {
+                ticker: instrument.ticker,
+                fh_ticker: None,
+                passed: false,
+                score: 0.0,
+                failure_reasons: vec![format!("excluded: {}", reason)],
+                screened_at,
+            };
+        }
+
+        let company: FullCompanyInfo = match get_full_company_info(instrument.clone()) {
+            Some(company) => company,
+            None => {
+                return ScreeningResult {
+                    ticker: instrument.ticker,
+                    fh_ticker: None,
+                    passed: false,
+                    score: 0.0,
+                    failure_reasons: vec!["company_data_unavailable".to_string()],
+                    screened_at,
+                }
+            }
+        };
+
+        let mut failure_reasons: Vec<String> = get_filter_failures(&company, false);
+
+        // Industry is only known once the company profile is in
+        let industry_exclusion: Option<String> =
+            get_exclusion_reason(&instrument, Some(&company.company_info.finnhub_industry));
+        if let Some(reason) = &industry_exclusion {
+            println!("ST: Excluding {} - {}.", instrument.ticker, reason);
+            failure_reasons.push(format!("excluded: {}", reason));
+        }
+
+        ScreeningResult {
+            ticker: instrument.ticker,
+            fh_ticker: Some(company.company_info.ticker.clone()),
+            // Bypassed tickers keep their failure reasons for reference. Exclusions are never bypassed
+            passed: industry_exclusion.is_none() && (bypass_filters || failure_reasons.is_empty()),
+            score: get_candidate_score(&company),
+            failure_reasons,
+            screened_at,
+        }
+    }
+
+    // Screens every instrument across SCREENING_WORKERS threads, sharing the Finnhub rate limit.
+    // Returns all results, passed instruments first in descending score order
+    pub fn run_screening_job(instruments: Vec<Instrument>) -> Vec<ScreeningResult> {
+        match std::fs::read_to_string(COMPLETED_PATH).ok() {
+            Some(completed_on) if !is_before_today(completed_on.trim()) => {
+                print_message(
+                    THREAD::FILTER,
+                    "Screening already completed today. Reusing its results",
+                );
+                let mut results: Vec<ScreeningResult> = load_progress();
+                sort_results(&mut results);
+                return results;
+            }
+            // Yesterday's finished run - start over. An unfinished run has no marker and resumes
+            Some(_) => {
+                let _ = std::fs::remove_file(PROGRESS_PATH);
+                let _ = std::fs::remove_file(COMPLETED_PATH);
+            }
+            None => {}
+        }
+
+        let resumed: Vec<ScreeningResult> = load_progress();
+        let completed: HashSet<String> =
+            resumed.iter().map(|result| result.ticker.clone()).collect();
+
+        let mut queue: VecDeque<Instrument> = instruments
+            .into_iter()
+            .filter(|instrument| !completed.contains(&instrument.ticker))
+            .collect();
+
+        let watchlist_tickers: HashSet<String> = get_watchlist_tickers();
+
+        // New listings and watchlist tickers get screened before the rest of the universe
+        let mut priority: HashSet<String> = HashSet::new();
+        if *SCREEN_NEW_LISTINGS_FIRST {
+            priority.extend(get_todays_new_listings());
+        }
+        if let WATCHLIST_SCREENING_MODE::PRIORITISE = *WATCHLIST_SCREENING {
+            priority.extend(watchlist_tickers.iter().cloned());
+        }
+        queue
+            .make_contiguous()
+            .sort_by_key(|instrument| !priority.contains(&instrument.ticker));
+
+        let bypassed: Arc<HashSet<String>> = Arc::new(match *WATCHLIST_SCREENING {
+            WATCHLIST_SCREENING_MODE::BYPASS => watchlist_tickers,
+            _ => HashSet::new(),
+        });
+
+        print_message(
+            THREAD::FILTER,
+            &format!(
+                "Screening {} instruments ({} resumed from checkpoint)...",
+                queue.len(),
+                completed.len()
+            ),
+        );
+
+        let progress_file: Option<File> = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(PROGRESS_PATH)
+            .ok();
+        if progress_file.is_none() {
+            print_message(
+                THREAD::FILE,
+                "Failed to open screening progress. Not resumable",
+            );
+        }
+
+        let arc_queue: Arc<Mutex<VecDeque<Instrument>>> = Arc::new(Mutex::new(queue));
+        let arc_results: Arc<Mutex<Vec<ScreeningResult>>> = Arc::new(Mutex::new(resumed));
+        let arc_progress: Arc<Mutex<Option<File>>> = Arc::new(Mutex::new(progress_file));
+
+        let workers: Vec<JoinHandle<()>> = (0..(*SCREENING_WORKERS).max(1))
+            .map(|i| {
+                let queue: Arc<Mutex<VecDeque<Instrument>>> = Arc::clone(&arc_queue);
+                let results: Arc<Mutex<Vec<ScreeningResult>>> = Arc::clone(&arc_results);
+                let progress: Arc<Mutex<Option<File>>> = Arc::clone(&arc_progress);
+                let bypassed: Arc<HashSet<String>> = Arc::clone(&bypassed);
+
+                thread::Builder::new()
+                    .name(format!("Screening_Worker_{}", i))
+                    .spawn(move || loop {
+                        let next: Option<Instrument> = queue.lock().unwrap().pop_front();
+                        let instrument: Instrument = match next {
+                            Some(instrument) => instrument,
+                            None => break,
+                        };
+
+                        let bypass_filters: bool = bypassed.contains(&instrument.ticker);
+                        let result: ScreeningResult = screen_instrument(instrument, bypass_filters);
+                        append_progress(&progress, &result);
+
+                        // Snapshot under the lock, sort and write outside it
+                        let snapshot: Option<Vec<ScreeningResult>> = {
+                            let mut results = results.lock().unwrap();
+                            results.push(result);
+                            (results.len() % PUBLISH_EVERY == 0).then(|| results.clone())
+                        };
+                        if let Some(mut snapshot) = snapshot {
+                            sort_results(&mut snapshot);
+                            write_screening_results_to_file(&snapshot);
+                        }
+                    })
+                    .expect("[Filter Thread] Failed to spawn screening worker")
+            })
+            .collect();
+
+        for worker in workers {
+            if worker.join().is_err() {
+                print_message(THREAD::FILTER, "A screening worker panicked");
+            }
+        }
+
+        let mut results: Vec<ScreeningResult> = std::mem::take(&mut arc_results.lock().unwrap());
+        sort_results(&mut results);
+
+        write_screening_results_to_file(&results);
+
+        // Kept for the rest of the day. The first run tomorrow starts from scratch
+        *arc_progress.lock().unwrap() = None;
+        if std::fs::write(COMPLETED_PATH, get_current_date(DateType::DMY)).is_err() {
+            print_message(THREAD::FILE, "Failed to mark screening as completed");
+        }
+
+        print_message(
+            THREAD::FILTER,
+            &format!(
+                "Screening complete. {}/{} passed.",
+                results.iter().filter(|result| result.passed).count(),
+                results.len()
+            ),
+        );
+
+        results
+    }
+}
@@ -0,0 +1,216 @@
+pub mod sentiment {
+    use std::collections::HashMap;
+
+    use chrono::Utc;
+    use serde::{Deserialize, Serialize};
+
+    use crate::helpers::types::types::NewsArticle;
+    use crate::NEWS_HALF_LIFE_HOURS;
+
+    const POSITIVE_WORDS: [(&str, f64); 32] = [
+        ("beat", 1.0),
+        ("beats", 1.0),
+        ("surge", 1.0),
+        ("surges", 1.0),
+        ("soar", 1.0),
+        ("soars", 1.0),
+        ("jump", 0.75),
+        ("jumps", 0.75),
+        ("rally", 0.75),
+        ("rallies", 0.75),
+        ("gain", 0.5),
+        ("gains", 0.5),
+        ("rise", 0.5),
+        ("rises", 0.5),
+        ("record", 0.5),
+        ("growth", 0.5),
+        ("profit", 0.5),
+        ("upgrade", 1.0),
+        ("upgraded", 1.0),
+        ("outperform", 0.75),
+        ("bullish", 1.0),
+        ("strong", 0.5),
+        ("raises", 0.75),
+        ("raised", 0.5),
+        ("buyback", 0.75),
+        ("dividend", 0.25),
+        ("approval", 0.75),
+        ("approved", 0.75),
+        ("partnership", 0.5),
+        ("expands", 0.5),
+        ("exceeds", 0.75),
+        ("optimistic", 0.75),
+    ];
+
+    const NEGATIVE_WORDS: [(&str, f64); 32] = [
+        ("miss", 1.0),
+        ("misses", 1.0),
+        ("plunge", 1.0),
+        ("plunges", 1.0),
+        ("tumble", 1.0),
+        ("tumbles", 1.0),
+        ("fall", 0.5),
+        ("falls", 0.5),
+        ("drop", 0.5),
+        ("drops", 0.5),
+        ("slump", 0.75),
+        ("loss", 0.75),
+        ("losses", 0.75),
+        ("downgrade", 1.0),
+        ("downgraded", 1.0),
+        ("underperform", 0.75),
+        ("bearish", 1.0),
+        ("weak", 0.5),
+        ("cuts", 0.75),
+        ("lawsuit", 1.0),
+        ("probe", 0.75),
+        ("investigation", 0.75),
+        ("fraud", 1.0),
+        ("recall", 0.75),
+        ("layoffs", 0.75),
+        ("bankruptcy", 1.0),
+        ("warning", 0.75),
+        ("warns", 0.75),
+        ("delay", 0.5),
+        ("delayed", 0.5),
+        ("halted", 0.75),
+        ("pessimistic", 0.75),
+    ];
+
+    const NEGATIONS: [&str; 6] = ["not", "no", "never", "without", "despite", "fails"];
+
+    // Headlines carry more signal than the summary text
+    const HEADLINE_WEIGHT: f64 = 2.0;
+    const SUMMARY_WEIGHT: f64 = 1.0;
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct ArticleScore {
+        pub id: i32,
+        pub source: String,
+        pub headline: String,
+        pub datetime: i64,
+        pub score: f64,                // -1.0 (negative) to 1.0 (positive)
+        pub recency_weight: f64,       // 1.0 for brand new articles, halving every half life
+        pub duplicate_of: Option<i32>, // Id of the article this was deduped against
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Default)]
+    pub struct NewsSentiment {
+        pub score: f64, // Recency weighted mean of unique article scores
+        pub scored_at: i64,
+        pub articles: Vec<ArticleScore>,
+    }
+
+    fn word_weight(word: &str) -> f64 {
+        if let Some((_, weight)) = POSITIVE_WORDS.iter().find(|(w, _)| *w == word) {
+            return *weight;
+        }
+        if let Some((_, weight)) = NEGATIVE_WORDS.iter().find(|(w, _)| *w == word) {
+            return -*weight;
+        }
+        0.0
+    }
+
+    // Returns the summed lexicon score and the number of sentiment words found
+    fn score_words(text: &str) -> (f64, f64) {
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_ascii_lowercase())
+            .collect();
+
+        let mut total: f64 = 0.0;
+        let mut hits: f64 = 0.0;
+
+        for (i, word) in words.iter().enumerate() {
+            let weight: f64 = word_weight(word);
+            if weight == 0.0 {
+                continue;
+            }
+
+            // "not beat", "no growth" - flip anything negated within the previous two words
+            let negated: bool = words[i.saturating_sub(2)..i]
+                .iter()
+                .any(|previous| NEGATIONS.contains(&previous.as_str()));
+
+            total += if negated { -weight } else { weight };
+            hits += 1.0;
+        }
+
+        (total, hits)
+    }
+
+    pub fn score_article(article: &NewsArticle) -> f64 {
+        let (headline_total, headline_hits) = score_words(&article.headline);
+        let (summary_total, summary_hits) = score_words(&article.summary);
+
+        let weighted_hits: f64 = headline_hits * HEADLINE_WEIGHT + summary_hits * SUMMARY_WEIGHT;
+        if weighted_hits == 0.0 {
+            return 0.0;
+        }
+
+        let weighted_total: f64 = headline_total * HEADLINE_WEIGHT + summary_total * SUMMARY_WEIGHT;
+        (weighted_total / weighted_hits).clamp(-1.0, 1.0)
+    }
+
+    fn get_recency_weight(article_time: i64, now: i64) -> f64 {
+        let age_hours: f64 = ((now - article_time).max(0) as f64) / 3600.0;
+        0.5f64.powf(age_hours / *NEWS_HALF_LIFE_HOURS)
+    }
+
+    // Syndicated stories are republished by several sources with the same headline
+    fn normalise_headline(headline: &str) -> String {
+        headline
+            .to_ascii_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect()
+    }
+
+    pub fn score_company_news(articles: &Vec<NewsArticle>) -> NewsSentiment {
+        let now: i64 = Utc::now().timestamp();
+
+        // Newest first so the original kept for each duplicate group is the most recent
+        let mut sorted: Vec<&NewsArticle> = articles.iter().collect();
+        sorted.sort_by(|a, b| b.datetime.cmp(&a.datetime));
+
+        let mut seen_headlines: HashMap<String, i32> = HashMap::new();
+        let mut scores: Vec<ArticleScore> = Vec::new();
+        let mut weighted_sum: f64 = 0.0;
+        let mut weight_total: f64 = 0.0;
+
+        for article in sorted {
+            let score: f64 = score_article(article);
+            let recency_weight: f64 = get_recency_weight(article.datetime, now);
+            let key: String = normalise_headline(&article.headline);
+            let duplicate_of: Option<i32> = seen_headlines.get(&key).copied();
+
+            if duplicate_of.is_none() {
+                seen_headlines.insert(key, article.id);
+                weighted_sum += score * recency_weight;
+                weight_total += recency_weight;
+            }
+
+            scores.push(ArticleScore {
+                id: article.id,
+                source: article.source.clone(),
+                headline: article.headline.clone(),
+                datetime: article.datetime,
+                score,
+                recency_weight,
+                duplicate_of,
+            });
+        }
+
+        let score: f64 = match weight_total > 0.0 {
+            true => weighted_sum / weight_total,
+            false => 0.0,
+        };
+
+        NewsSentiment {
+            score,
+            scored_at: now,
+            articles: scores,
+        }
+    }
+}
@@ -0,0 +1,145 @@
+pub mod sizing {
+    use bigdecimal::BigDecimal;
+
+    use crate::control::profiles::profiles::get_profile_strategy;
+    use crate::file_control::read::read::get_sale_results_from_file;
+    use crate::file_control::types::types::SaleResult;
+    use crate::finnhub::utils::helpers::FHStockData;
+    use crate::helpers::helpers::helpers::calculate_amount_spent_per_ticker;
+    use crate::helpers::money::money::{decimal_from_f64, Money};
+    use crate::helpers::types::types::FullCompanyInfo;
+    use crate::trading212::types::types::BalanceObject;
+    use crate::{
+        KELLY_FRACTION, KELLY_MAX_PERC, KELLY_MIN_TRADES, RISK_PER_TRADE, SIZING_MODEL,
+        VOLATILITY_MAX_SCALE, VOLATILITY_MEASURE, VOLATILITY_TARGET_RANGE,
+    };
+
+    pub struct JournalStats {
+        pub trades: usize,
+        pub win_rate: f64,
+        pub payoff_ratio: f64, // Average win over average loss
+    }
+
+    // Win rate and payoff from every sale recorded in the current profile's journal
+    pub fn get_journal_stats() -> Option<JournalStats> {
+        let sales: Vec<SaleResult> = get_sale_results_from_file()?;
+
+        let wins: Vec<f64> = sales
+            .iter()
+            .filter(|sale| sale.profit > 0.0)
+            .map(|sale| sale.profit)
+            .collect();
+        let losses: Vec<f64> = sales
+            .iter()
+            .filter(|sale| sale.profit < 0.0)
+            .map(|sale| -sale.profit)
+            .collect();
+
+        if wins.is_empty() || losses.is_empty() {
+            return None;
+        }
+
+        let average_win: f64 = wins.iter().sum::<f64>() / wins.len() as f64;
+        let average_loss: f64 = losses.iter().sum::<f64>() / losses.len() as f64;
+
+        Some(JournalStats {
+            trades: sales.len(),
+            win_rate: wins.len() as f64 / sales.len() as f64,
+            payoff_ratio: average_win / average_loss,
+        })
+    }
+
+    // Today's true range as a fraction of price - the closest to an ATR the quote gives
+    fn get_range_perc(quote: &FHStockData) -> Option<f64> {
+        if quote.current_price <= 0.0 || quote.todays_high <= 0.0 || quote.todays_low <= 0.0 {
+            return None;
+        }
+
+        let true_range: f64 = (quote.todays_high - quote.todays_low)
+            .max((quote.todays_high - quote.previous_close).abs())
+            .max((quote.todays_low - quote.previous_close).abs());
+
+        match true_range > 0.0 {
+            true => Some(true_range / quote.current_price),
+            false => None,
+        }
+    }
+
+    // How much to scale the equal weight budget so every position carries similar volatility
+    fn get_volatility_scale(company: &FullCompanyInfo, measure: &VOLATILITY_MEASURE) -> f64 {
+        let scale: Option<f64> = match measure {
+            // Beta 1 moves with the market, so it keeps the equal weight budget
+            VOLATILITY_MEASURE::BETA => {
+                let beta: f64 = company.company_financials.metric.beta;
+                (beta > 0.0).then(|| 1.0 / beta)
+            }
+            VOLATILITY_MEASURE::ATR => get_range_perc(&company.company_stock_quote)
+                .map(|range_perc| *VOLATILITY_TARGET_RANGE / range_perc),
+        };
+
+        match scale {
+            Some(scale) => scale.min(*VOLATILITY_MAX_SCALE),
+            None => {
+                println!(
+                    "BT: No {:?} for {}. Using equal weight.",
+                    measure, company.instrument.ticker
+                );
+                1.0
+            }
+        }
+    }
+
+    fn get_kelly_fraction(stats: &JournalStats) -> f64 {
+        let kelly: f64 = stats.win_rate - (1.0 - stats.win_rate) / stats.payoff_ratio;
+        (kelly * *KELLY_FRACTION).min(*KELLY_MAX_PERC)
+    }
+
+    // Amount of account currency to put into one company under the configured sizing model.
+    // None when the model says not to trade
+    pub fn get_position_budget(
+        company: &FullCompanyInfo,
+        balance_data: &BalanceObject,
+    ) -> Option<Money> {
+        let equal_weight: Money = calculate_amount_spent_per_ticker(balance_data);
+
+        // No single position may take more than the whole cycle's budget
+        let cycle_budget: BigDecimal = decimal_from_f64(balance_data.free)
+            * decimal_from_f64(1.0 - get_profile_strategy().spend_perc.clamp(0.0, 1.0));
+
+        let budget: BigDecimal = match get_profile_strategy().position_sizing {
+            SIZING_MODEL::EQUAL_WEIGHT => equal_weight.0,
+            SIZING_MODEL::VOLATILITY_TARGET(measure) => {
+                equal_weight.0 * decimal_from_f64(get_volatility_scale(company, measure))
+            }
+            SIZING_MODEL::FIXED_FRACTION_RISK => {
+                // Losing the full stop distance costs RISK_PER_TRADE of the free balance
+                let stop_distance: f64 = get_profile_strategy().sell_percent_down.abs();
+                if stop_distance == 0.0 {
+                    return Some(equal_weight);
+                }
+                decimal_from_f64(balance_data.free)
+                    * decimal_from_f64(*RISK_PER_TRADE / stop_distance)
+            }
+            SIZING_MODEL::CAPPED_KELLY => match get_journal_stats() {
+                Some(stats) if stats.trades >= *KELLY_MIN_TRADES => {
+                    let fraction: f64 = get_kelly_fraction(&stats);
+                    if fraction <= 0.0 {
+                        println!(
+                            "BT: Kelly fraction is {:.3} ({} trades, {:.1}% wins). No edge - skipping {}.",
+                            fraction,
+                            stats.trades,
+                            stats.win_rate * 100.0,
+                            company.instrument.ticker
+                        );
+                        return None;
+                    }
+                    decimal_from_f64(balance_data.free) * decimal_from_f64(fraction)
+                }
+                // Too few sales to trust the win rate yet
+                _ => equal_weight.0,
+            },
+        };
+
+        Some(Money(budget.min(cycle_budget)))
+    }
+}
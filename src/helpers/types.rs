@@ -3,6 +3,8 @@ pub mod types {
 
     use crate::{
         finnhub::utils::helpers::{CompanyInfo, FHStockData},
+        helpers::peers::peers::PeerScore,
+        helpers::sentiment::sentiment::NewsSentiment,
         trading212::types::types::Instrument,
     };
 
@@ -30,12 +32,20 @@ pub mod types {
         // https://finnhub.io/docs/api/company-news
         pub company_news: Vec<NewsArticle>,
         //
+        // Lexicon score of company_news, kept per article for auditing
+        #[serde(default)]
+        pub news_sentiment: NewsSentiment,
+        //
         // https://finnhub.io/docs/api/company-peers
         pub company_peers: Option<Vec<String>>,
         //
         // https://finnhub.io/docs/api/company-basic-financials
         pub company_financials: CompanyFinancials,
         //
+        // Percentile ranking against company_peers
+        #[serde(default)]
+        pub peer_score: Option<PeerScore>,
+        //
         // https://finnhub.io/docs/api/insider-sentiment
         pub company_sentiment: Vec<SentimentData>,
         //
@@ -53,7 +63,7 @@ pub mod types {
         pub symbol: String,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct InsiderTransaction {
         pub name: String,
         pub share: i64,
@@ -68,7 +78,7 @@ pub mod types {
         pub transaction_price: f64,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct NewsArticle {
         pub category: String,
         pub datetime: i64,
@@ -81,13 +91,13 @@ pub mod types {
         pub url: String,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct DataPoint {
         pub period: String,
         pub v: f64,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct AnnualSeries {
         #[serde(rename = "currentRatio")]
         pub current_ratio: Option<Vec<DataPoint>>,
@@ -97,12 +107,12 @@ pub mod types {
         pub net_margin: Option<Vec<DataPoint>>,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct Series {
         pub annual: AnnualSeries,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct Metric {
         #[serde(rename = "10DayAverageTradingVolume")]
         pub avg_trading_volume_10_day: Option<f64>,
@@ -115,9 +125,13 @@ pub mod types {
         #[serde(rename = "52WeekPriceReturnDaily")]
         pub week_price_return_daily_52: Option<f64>,
         pub beta: f64,
+        #[serde(rename = "peTTM")]
+        pub pe_ttm: Option<f64>,
+        #[serde(rename = "netProfitMarginTTM")]
+        pub net_profit_margin_ttm: Option<f64>,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct CompanyFinancials {
         pub series: Series,
         pub metric: Metric,
@@ -126,7 +140,7 @@ pub mod types {
         pub symbol: String,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct SentimentData {
         pub symbol: String, // Symbol of the company
         pub year: u32,      // Year of the transaction
@@ -160,19 +174,19 @@ pub mod types {
         pub earnings_calendar: Vec<EarningsRelease>,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct EarningsRelease {
         pub date: String, // Date of the earnings release
         #[serde(rename = "epsActual")]
-        pub eps_actual: f64, // Actual EPS (Earnings Per Share)
+        pub eps_actual: Option<f64>, // Actual EPS (Earnings Per Share) - null for upcoming releases
         #[serde(rename = "epsEstimate")]
-        pub eps_estimate: f64, // Estimated EPS
+        pub eps_estimate: Option<f64>, // Estimated EPS
         pub hour: String, // Time of the earnings release (bmo, amc, or dmh)
         pub quarter: u32, // Fiscal quarter
         #[serde(rename = "revenueActual")]
-        pub revenue_actual: u64, // Actual revenue
+        pub revenue_actual: Option<u64>, // Actual revenue - null for upcoming releases
         #[serde(rename = "revenueEstimate")]
-        pub revenue_estimate: u64, // Estimated revenue
+        pub revenue_estimate: Option<u64>, // Estimated revenue
         pub symbol: String, // Company symbol
         pub year: u32,    // Fiscal year
     }
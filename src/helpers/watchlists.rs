@@ -0,0 +1,270 @@
+pub mod watchlists {
+    use std::collections::HashSet;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::file_control::read::read::get_watchlists_from_file;
+    use crate::file_control::write::write::write_watchlists_to_file;
+    use crate::finnhub::symbols::symbols::lookup_fh_symbol;
+    use crate::finnhub::utils::helpers::FHStockData;
+    use crate::helpers::helpers::helpers::{
+        get_cached_quote, get_current_date, get_current_time, print_message, THREAD,
+    };
+    use crate::helpers::types::types::DateType;
+    use crate::{WATCHLIST_SCREENING, WATCHLIST_SCREENING_MODE};
+
+    #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+    pub enum ALERT_KIND {
+        PRICE_ABOVE,    // Current price at or above the threshold
+        PRICE_BELOW,    // Current price at or below the threshold
+        PERCENT_CHANGE, // Move since the previous close of at least threshold % either way
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct PriceAlert {
+        pub kind: ALERT_KIND,
+        pub threshold: f64,
+        pub triggered_on: Option<String>, // DMY - each alert fires at most once a day
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct WatchlistItem {
+        pub ticker: String, // Trading212 ticker
+        pub added_at: String,
+        #[serde(default)]
+        pub alerts: Vec<PriceAlert>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct Watchlist {
+        pub name: String,
+        pub items: Vec<WatchlistItem>,
+    }
+
+    fn find_list<'a>(lists: &'a mut Vec<Watchlist>, name: &str) -> Option<&'a mut Watchlist> {
+        lists
+            .iter_mut()
+            .find(|list| list.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn add_to_watchlist(name: &str, ticker: &str) {
+        let mut lists: Vec<Watchlist> = get_watchlists_from_file().unwrap_or_default();
+
+        if find_list(&mut lists, name).is_none() {
+            lists.push(Watchlist {
+                name: name.to_string(),
+                items: Vec::new(),
+            });
+        }
+        let list: &mut Watchlist = find_list(&mut lists, name).unwrap();
+
+        if list
+            .items
+            .iter()
+            .any(|item| item.ticker.eq_ignore_ascii_case(ticker))
+        {
+            println!("{} is already on {}.", ticker, list.name);
+            return;
+        }
+
+        list.items.push(WatchlistItem {
+            ticker: ticker.to_string(),
+            added_at: get_current_time(),
+            alerts: Vec::new(),
+        });
+        println!("Added {} to {}.", ticker, list.name);
+
+        write_watchlists_to_file(&lists);
+    }
+
+    pub fn remove_from_watchlist(name: &str, ticker: &str) {
+        let mut lists: Vec<Watchlist> = get_watchlists_from_file().unwrap_or_default();
+
+        let list: &mut Watchlist = match find_list(&mut lists, name) {
+            Some(list) => list,
+            None => {
+                println!("No watchlist named {}.", name);
+                return;
+            }
+        };
+
+        let before: usize = list.items.len();
+        list.items
+            .retain(|item| !item.ticker.eq_ignore_ascii_case(ticker));
+
+        if list.items.len() == before {
+            println!("{} is not on {}.", ticker, list.name);
+            return;
+        }
+        println!("Removed {} from {}.", ticker, list.name);
+
+        // Empty lists are dropped
+        lists.retain(|list| !list.items.is_empty());
+        write_watchlists_to_file(&lists);
+    }
+
+    pub fn add_alert(name: &str, ticker: &str, kind: ALERT_KIND, threshold: f64) {
+        let mut lists: Vec<Watchlist> = get_watchlists_from_file().unwrap_or_default();
+
+        let item: Option<&mut WatchlistItem> = find_list(&mut lists, name).and_then(|list| {
+            list.items
+                .iter_mut()
+                .find(|item| item.ticker.eq_ignore_ascii_case(ticker))
+        });
+
+        match item {
+            Some(item) => {
+                println!("Added {:?} {} alert to {}.", kind, threshold, item.ticker);
+                item.alerts.push(PriceAlert {
+                    kind,
+                    threshold,
+                    triggered_on: None,
+                });
+                write_watchlists_to_file(&lists);
+            }
+            None => println!("{} is not on {}.", ticker, name),
+        }
+    }
+
+    pub fn print_watchlists(name: Option<&str>) {
+        let lists: Vec<Watchlist> = get_watchlists_from_file().unwrap_or_default();
+
+        let shown: Vec<&Watchlist> = lists
+            .iter()
+            .filter(|list| name.map_or(true, |name| list.name.eq_ignore_ascii_case(name)))
+            .collect();
+
+        if shown.is_empty() {
+            println!("No watchlists found.");
+            return;
+        }
+
+        for list in shown {
+            println!("\n{} ({} tickers)", list.name, list.items.len());
+            for item in &list.items {
+                println!("  {} - added {}", item.ticker, item.added_at);
+                for alert in &item.alerts {
+                    println!("    {:?} {}", alert.kind, alert.threshold);
+                }
+            }
+        }
+    }
+
+    // Every ticker on any watchlist
+    pub fn get_watchlist_tickers() -> HashSet<String> {
+        get_watchlists_from_file()
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|list| list.items)
+            .map(|item| item.ticker)
+            .collect()
+    }
+
+    pub fn is_filter_bypassed(ticker: &String) -> bool {
+        match *WATCHLIST_SCREENING {
+            WATCHLIST_SCREENING_MODE::BYPASS => get_watchlist_tickers().contains(ticker),
+            _ => false,
+        }
+    }
+
+    fn is_alert_triggered(alert: &PriceAlert, quote: &FHStockData) -> bool {
+        match alert.kind {
+            ALERT_KIND::PRICE_ABOVE => quote.current_price >= alert.threshold,
+            ALERT_KIND::PRICE_BELOW => quote.current_price <= alert.threshold,
+            ALERT_KIND::PERCENT_CHANGE => {
+                if quote.previous_close <= 0.0 {
+                    return false;
+                }
+                let change_perc: f64 =
+                    (quote.current_price - quote.previous_close) / quote.previous_close * 100.0;
+                change_perc.abs() >= alert.threshold.abs()
+            }
+        }
+    }
+
+    // Run once per control cycle - quotes come from the company cache so this stays cheap
+    pub fn evaluate_watchlist_alerts() {
+        let mut lists: Vec<Watchlist> = match get_watchlists_from_file() {
+            Some(lists) => lists,
+            None => return,
+        };
+
+        let today: String = get_current_date(DateType::DMY);
+        let mut fired: bool = false;
+
+        for list in lists.iter_mut() {
+            for item in list.items.iter_mut() {
+                if item
+                    .alerts
+                    .iter()
+                    .all(|alert| alert.triggered_on.as_ref() == Some(&today))
+                {
+                    continue;
+                }
+
+                let quote: FHStockData = match get_cached_quote(&lookup_fh_symbol(&item.ticker)) {
+                    Some(quote) => quote,
+                    None => continue,
+                };
+
+                for alert in item.alerts.iter_mut() {
+                    if alert.triggered_on.as_ref() == Some(&today)
+                        || !is_alert_triggered(alert, &quote)
+                    {
+                        continue;
+                    }
+
+                    print_message(
+                        THREAD::CONTROL,
+                        &format!(
+                            "ALERT [{}] {} {:?} {} - price {}",
+                            list.name,
+                            item.ticker,
+                            alert.kind,
+                            alert.threshold,
+                            quote.current_price
+                        ),
+                    );
+                    alert.triggered_on = Some(today.clone());
+                    fired = true;
+                }
+            }
+        }
+
+        if fired {
+            write_watchlists_to_file(&lists);
+        }
+    }
+
+    fn print_usage() {
+        println!("Usage:");
+        println!("  apollo watchlist add <list> <ticker>");
+        println!("  apollo watchlist remove <list> <ticker>");
+        println!("  apollo watchlist list [list]");
+        println!("  apollo watchlist alert <list> <ticker> <above|below|change> <value>");
+    }
+
+    // args are everything after "watchlist"
+    pub fn run_watchlist_command(args: &[String]) {
+        let arg = |i: usize| -> Option<&str> { args.get(i).map(|arg| arg.as_str()) };
+
+        match (arg(0), arg(1), arg(2)) {
+            (Some("add"), Some(name), Some(ticker)) => add_to_watchlist(name, ticker),
+            (Some("remove"), Some(name), Some(ticker)) => remove_from_watchlist(name, ticker),
+            (Some("list"), name, _) => print_watchlists(name),
+            (Some("alert"), Some(name), Some(ticker)) => {
+                let kind: ALERT_KIND = match arg(3) {
+                    Some("above") => ALERT_KIND::PRICE_ABOVE,
+                    Some("below") => ALERT_KIND::PRICE_BELOW,
+                    Some("change") => ALERT_KIND::PERCENT_CHANGE,
+                    _ => return print_usage(),
+                };
+                match arg(4).and_then(|value| value.parse::<f64>().ok()) {
+                    Some(threshold) => add_alert(name, ticker, kind, threshold),
+                    None => print_usage(),
+                }
+            }
+            _ => print_usage(),
+        }
+    }
+}
@@ -1,23 +1,44 @@
+// The config lazy_static block is past the default macro recursion limit
+#![recursion_limit = "256"]
+
 mod control;
 mod file_control;
 mod finnhub;
 mod helpers;
 mod trading212;
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc;
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
 
+use control::buy_channel::buy_channel::start_buying;
+use control::checkpoint::checkpoint::{load_checkpoint, set_cycle_stage, CYCLE_STAGE};
+use control::control_channel::control_channel::start_control;
+use control::messages::messages::{await_cycle_message, send_message, CYCLE_MESSAGE};
+use control::profiles::profiles::{set_profile, validate_profiles};
+use control::scheduler::scheduler::{print_job_statuses, Scheduler};
+use control::sell_channel::sell_channel::start_selling;
+use control::shutdown::shutdown::{listen_for_shutdown, ProfileWorkers};
+use control::supervisor::supervisor::{run_health_command, spawn_supervised, start_health_monitor};
 use dotenv::dotenv;
-use file_control::read::read::{get_instruments_from_file, get_positions_from_file};
+use file_control::read::read::{
+    get_exchanges_from_file, get_instruments_from_file, get_positions_from_file,
+    get_previous_instruments_from_file,
+};
 use file_control::write::write::{
-    write_filtered_instruments_to_file, write_instruments_to_file, write_positions_to_file,
+    log_instrument_diff, write_exchanges_to_file, write_filtered_instruments_to_file,
+    write_instruments_to_file, write_positions_to_file,
 };
 use helpers::filters::filtering::filter_trading212_instruments;
 use helpers::helpers::helpers::{print_message, THREAD};
+use helpers::listings::listings::diff_instruments;
+use helpers::watchlists::watchlists::run_watchlist_command;
 use lazy_static::lazy_static;
 use std::sync::{Arc, Mutex};
-use trading212::helpers::helpers::{get_account_balance, get_all_positions, get_instruments};
+use trading212::helpers::helpers::{
+    get_account_balance, get_all_positions, get_exchanges, get_instruments,
+};
+use trading212::orders::orders::OrderManager;
 use trading212::types::types::{BalanceObject, Instrument};
 use trading212::types::types::{LimitOrder, Position};
 
@@ -27,120 +48,509 @@ pub enum VERSION {
     DEMO,
 }
 
+#[derive(Debug)]
+pub enum SCHEDULE {
+    INTERVAL(u64),      // Every n seconds, starting at launch
+    DAILY_AT(u32, u32), // Once a day at hour, minute UTC
+}
+
+#[derive(Debug)]
+pub enum SHUTDOWN_MODE {
+    FINISH, // Run the current sell ladder to the end, then exit
+    CANCEL, // Stop the ladder and cancel Apollo's open orders
+    LEAVE,  // Stop the ladder and leave open orders working - the next start picks them up
+}
+
 #[derive(Debug)]
 pub enum FILTERING_STRICTNESS {
     STRONG,
     LIGHT,
 }
 
+#[derive(Debug)]
+pub enum WATCHLIST_SCREENING_MODE {
+    OFF,        // Watchlists have no effect on screening
+    PRIORITISE, // Screen watchlist tickers before the rest of the universe
+    BYPASS,     // Watchlist tickers with company data always pass the filters
+}
+
+#[derive(Debug)]
+pub enum ENTRY_MODE {
+    MARKET,           // Market order - always fills, pays the spread
+    AT_QUOTE,         // Limit at the last Finnhub quote
+    BELOW_QUOTE(u32), // Limit this many basis points under the quote
+    MID_HIGH_LOW,     // Limit at the middle of today's range, capped at the quote
+    PATIENT_LADDER, // Start PATIENT_LADDER_START_BPS under the quote and reprice up to it over LIMIT_WAIT_TIME
+}
+
+#[derive(Debug)]
+pub enum VOLATILITY_MEASURE {
+    BETA, // Finnhub beta against the market
+    ATR,  // Today's true range from the quote
+}
+
+#[derive(Debug)]
+pub enum SIZING_MODEL {
+    EQUAL_WEIGHT, // Same amount per ticker - SPEND_PERC over AMOUNT_OF_TICKERS_TO_BUY
+    VOLATILITY_TARGET(VOLATILITY_MEASURE), // Equal weight scaled down for volatile tickers and up for quiet ones
+    FIXED_FRACTION_RISK, // Size so a SELL_PERCENT_DOWN stop loses RISK_PER_TRADE of the balance
+    CAPPED_KELLY,        // Kelly fraction from the win rate in sale_results.json
+}
+
+#[derive(Debug)]
+pub enum RECONCILE_MODE {
+    ADOPT, // Manage positions found at the broker as if Apollo created them. Unknown orders are tracked but never cancelled
+    IGNORE, // Leave them alone - the sell thread only sees positions Apollo opened
+}
+
+// Instruments Apollo never screens, buys or sells. Matching is case insensitive
+#[derive(Debug)]
+pub struct ExclusionPolicy {
+    pub ticker_patterns: Vec<&'static str>, // Matched anywhere in the Trading212 ticker
+    pub isins: Vec<&'static str>,
+    pub asset_types: Vec<&'static str>, // Trading212 instrument types - "ETF", "STOCK", ...
+    pub currencies: Vec<&'static str>,
+    pub industries: Vec<&'static str>, // Finnhub industries - "Banking", "Pharmaceuticals", ...
+}
+
+#[derive(Debug)]
+pub enum EARNINGS_EXIT_MODE {
+    OFF,     // Hold positions through earnings
+    TIGHTEN, // Skip straight to the tightest rungs of the sell ladder
+    EXIT,    // Market sell before the release
+}
+
+#[derive(Debug)]
+pub enum FX_PROVIDER {
+    FINNHUB,
+    LOCAL_FILE,
+}
+
+// Per profile trading settings. StrategyConfig::default() takes them from the global config below
+#[derive(Debug)]
+pub struct StrategyConfig {
+    pub entry_pricing: &'static ENTRY_MODE,
+    pub position_sizing: &'static SIZING_MODEL,
+    pub tickers_to_buy: usize, // Splits the cycle budget under EQUAL_WEIGHT
+    pub spend_perc: f64,
+    pub minimum_buys: usize,
+    pub sell_percent_down: f64,
+    pub sell_percent_up: f64,
+    pub use_brackets: bool,
+    pub earnings_exit: &'static EARNINGS_EXIT_MODE,
+}
+
+// One Trading212 account and the strategy trading it
+#[derive(Debug)]
+pub struct AccountProfile {
+    pub name: &'static str,        // Prefixes the profile's worker and job names
+    pub version: VERSION,          // Picks the default base url
+    pub api_key_var: &'static str, // Environment variable holding the account's API key
+    pub base_url: Option<&'static str>, // Overrides the DEMO/LIVE base url
+    pub data_dir: &'static str,    // Journals, checkpoint, ledger and order state
+    pub strategy: StrategyConfig,
+}
+
 lazy_static! {
-    pub static ref STOCK_VERSION: VERSION = VERSION::DEMO; // Live or testing on practise account
+    // Profile Config - every profile runs its own control, buy and sell workers in this process.
+    // Instruments, screening and company data are shared and stay in src/data
+    pub static ref PROFILES: Vec<AccountProfile> = vec![
+        AccountProfile {
+            name: "demo",
+            version: VERSION::DEMO,
+            api_key_var: "DEMO_API_KEY",
+            base_url: None,
+            data_dir: "src/data",
+            strategy: StrategyConfig::default(),
+        },
+        // Trialling a candidate strategy on demo alongside the proven one on live:
+        // AccountProfile {
+        //     name: "live",
+        //     version: VERSION::LIVE,
+        //     api_key_var: "LIVE_API_KEY",
+        //     base_url: None,
+        //     data_dir: "src/data/live",
+        //     strategy: StrategyConfig::default(),
+        // },
+        // with the demo profile's strategy set to
+        //     StrategyConfig { entry_pricing: &ENTRY_MODE::PATIENT_LADDER, ..StrategyConfig::default() }
+    ];
 
     // Minimum amount of seconds to await limit orders to hit
     // 300 = 5 minutes
     // 180 = 3 minutes
     pub static ref LIMIT_WAIT_TIME: u64 = 60;
 
+    // Scheduler Config - a job that is still running when it comes due again is logged as a missed run
+    pub static ref INSTRUMENTS_REFRESH_SCHEDULE: SCHEDULE = SCHEDULE::INTERVAL(60 * 60); // Re-downloads once the file is out of date
+    pub static ref SCREENING_SCHEDULE: SCHEDULE = SCHEDULE::INTERVAL(60 * 60); // Resumes an unfinished run and does nothing once a run has completed that day
+    pub static ref CONTROL_REFRESH_SCHEDULE: SCHEDULE = SCHEDULE::INTERVAL(15 * 60); // One full control, buy and sell cycle per run
+    pub static ref SELL_LADDER_STEP_INTERVAL: u64 = 180; // Seconds each sell ladder rung is left working
+    pub static ref ORDER_SPACING: u64 = 10; // Seconds between consecutive order requests
+
+    // Shutdown Config - on SIGINT/SIGTERM no new cycles or buys start, then in-flight orders are handled by the policy
+    pub static ref SHUTDOWN_POLICY: SHUTDOWN_MODE = SHUTDOWN_MODE::CANCEL;
+    pub static ref SHUTDOWN_GRACE_PERIOD: u64 = 600; // Most seconds to wait for the current cycle before exiting anyway
+
+    // Supervisor Config - health is written to src/data/health.json and shown by "apollo health"
+    pub static ref HANDSHAKE_TIMEOUT: u64 = 30 * 60; // Longest the buy and sell threads wait on each other mid-cycle
+    pub static ref CYCLE_TIMEOUT: u64 = 60 * 60; // Longest a whole control, buy and sell cycle may take
+    pub static ref HEARTBEAT_INTERVAL: u64 = 60; // Seconds between heartbeats while waiting, and between health checks
+    pub static ref WORKER_STALL_TIMEOUT: u64 = 15 * 60; // A worker silent for this long is reported as stalled
+    pub static ref WORKER_RESTART_DELAY: u64 = 30; // Seconds before a failed worker is started again
+    pub static ref WORKER_MAX_RESTARTS: u32 = 10; // Restarts in a row before a worker is left dead
+    pub static ref WORKER_STABLE_RUN: u64 = 60 * 60; // A run this long clears the restart count
+
     // Filtering Config
     pub static ref FILTER_STRICTNESS: FILTERING_STRICTNESS = FILTERING_STRICTNESS::LIGHT;
     pub static ref MINIMUM_MARKET_CAP: f64 = 2000.0; // Minimum market cap - 2bil?
+    pub static ref MINIMUM_NEWS_SENTIMENT: f64 = -0.1; // -1.0 to 1.0 - Lowest acceptable recent news sentiment
+    pub static ref NEWS_HALF_LIFE_HOURS: f64 = 24.0; // Article weight halves every 24 hours
+    pub static ref EARNINGS_BLACKOUT_DAYS: i64 = 2; // No new buys within 2 days of an earnings release
+    pub static ref EARNINGS_LOOKAHEAD_DAYS: i64 = 30; // How far ahead to fetch upcoming earnings releases
+    pub static ref PEER_COMPARISON_SIZE: usize = 5; // Peers fetched per candidate - 0 disables peer ranking
+    pub static ref MINIMUM_PEER_PERCENTILE: f64 = 0.5; // 0.0 to 1.0 - Candidate must beat this share of its peers
+    pub static ref INSIDER_SENTIMENT_MONTHS: u32 = 3; // Months of insider sentiment to read the MSPR trend from
+    pub static ref MINIMUM_MSPR: f64 = 0.0; // -100 to 100 - Lowest acceptable recency weighted MSPR
+
+    // Company Data Cache Config - seconds each field stays fresh
+    pub static ref QUOTE_CACHE_TTL: u64 = 30;
+    pub static ref NEWS_CACHE_TTL: u64 = 60 * 60; // 1 hour
+    pub static ref FINANCIALS_CACHE_TTL: u64 = 60 * 60 * 24; // 1 day - also insider data and earnings
+    pub static ref PROFILE_CACHE_TTL: u64 = 60 * 60 * 24; // 1 day
+    pub static ref PEERS_CACHE_TTL: u64 = 60 * 60 * 24 * 7; // 1 week
 
-    // Buy Config
+    // Screening Config
+    pub static ref FH_CALLS_PER_MINUTE: u32 = 55; // Finnhub free plan allows 60 calls a minute
+    pub static ref SCREENING_WORKERS: usize = 4; // Threads screening instruments concurrently
+    pub static ref SCREEN_NEW_LISTINGS_FIRST: bool = true; // Move today's new listings to the front of the screening queue
+    pub static ref INSTRUMENT_DIFF_HISTORY: usize = 90; // Daily instrument diffs kept on file
+    pub static ref WATCHLIST_SCREENING: WATCHLIST_SCREENING_MODE = WATCHLIST_SCREENING_MODE::PRIORITISE;
+
+    // Buy Config - settings shared with StrategyConfig are defaults for profiles that keep them
     pub static ref MINIMUM_BALANCE: f64 = 15000.0; // Minimum acceptable balance to execute buys
     pub static ref AMOUNT_OF_TICKERS_TO_BUY: usize = 10; // Amount of tickers to buy per cycle
     pub static ref SPEND_PERC: f64 = 0.05; // % of balance to spend per cycle 0.1 = 10%
     pub static ref SHOPPING_TIME: u64 = 300; // Time per shopping cycle - 5 minutes
     pub static ref MINIMUM_BUYS: usize = 3; // Minimum amount of buys per cycle
+    pub static ref ENTRY_PRICING: ENTRY_MODE = ENTRY_MODE::BELOW_QUOTE(10); // How buy orders are priced - fill rates per mode print after each cycle
+    pub static ref PATIENT_LADDER_STEPS: u32 = 4; // Reprices before the ladder reaches the quote
+    pub static ref PATIENT_LADDER_START_BPS: u32 = 25; // 25 = 0.25% under the quote
+
+    // Sizing Config - every model is capped at the cycle budget and the instrument's trade quantity limits
+    pub static ref POSITION_SIZING: SIZING_MODEL = SIZING_MODEL::EQUAL_WEIGHT;
+    pub static ref VOLATILITY_TARGET_RANGE: f64 = 0.02; // Daily range that gets the equal weight amount under ATR - 0.02 = 2%
+    pub static ref VOLATILITY_MAX_SCALE: f64 = 2.0; // Most a quiet ticker can be scaled above equal weight
+    pub static ref RISK_PER_TRADE: f64 = 0.005; // Balance lost if a fixed fraction position is stopped out - 0.005 = 0.5%
+    pub static ref KELLY_FRACTION: f64 = 0.5; // Share of the full Kelly bet to take - 0.5 = half Kelly
+    pub static ref KELLY_MAX_PERC: f64 = 0.1; // Hard cap on the Kelly bet as a share of the balance
+    pub static ref KELLY_MIN_TRADES: usize = 20; // Sales needed before Kelly is trusted - equal weight until then
+
+    // Currency Config
+    pub static ref ACCOUNT_CURRENCY: &'static str = "GBP"; // Currency the Trading212 account is held in
+    pub static ref FX_RATE_PROVIDER: FX_PROVIDER = FX_PROVIDER::FINNHUB; // LOCAL_FILE reads src/data/fx_rates_local.json
+    pub static ref FX_FEE_PERC: f64 = 0.15; // Trading212 FX fee per conversion - 0.15%
+
+    // Order Config
+    pub static ref DEFAULT_TICK_SIZE: f64 = 0.01; // Smallest price increment for limit orders
+    pub static ref TICK_SIZE_OVERRIDES: Vec<(&'static str, f64)> = vec![]; // (Trading212 ticker or currency code, tick) pairs used instead of DEFAULT_TICK_SIZE
+    pub static ref TRADE_EXTENDED_HOURS: bool = false; // Allow trading in pre-market, after-hours and overnight sessions
+
+    // Exclusion Config
+    pub static ref EXCLUSION_POLICY: ExclusionPolicy = ExclusionPolicy {
+        ticker_patterns: vec!["vusa"], // Held long term outside Apollo
+        isins: vec![],
+        asset_types: vec![],
+        currencies: vec![],
+        industries: vec![],
+    };
+
+    // Order Ownership Config
+    pub static ref PROTECTED_TICKERS: Vec<&'static str> = vec![]; // Trading212 tickers Apollo never buys, sells or cancels
+
+    // Reconciliation Config
+    pub static ref RECONCILIATION_POLICY: RECONCILE_MODE = RECONCILE_MODE::ADOPT; // What to do with positions and orders Apollo did not create
 
     // Sell Config
-    pub static ref SELL_PERCENT_DOWN: f64 = -0.025;
-    pub static ref SELL_PERCENT_UP: f64 = 0.05;
+    pub static ref SELL_PERCENT_DOWN: f64 = -0.025; // Fraction of the average price, -0.025 is a 2.5% drop
+    pub static ref SELL_PERCENT_UP: f64 = 0.05; // Fraction of the average price, 0.05 is a 5% rise
+    pub static ref USE_BRACKETS: bool = false; // Hold a take profit (SELL_PERCENT_UP) and stop (SELL_PERCENT_DOWN) per position instead of the sell ladder
+    pub static ref EARNINGS_EXIT: EARNINGS_EXIT_MODE = EARNINGS_EXIT_MODE::OFF; // What to do with held positions ahead of earnings
 
 }
 
 // Data to be sent across threads during execution
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ChannelParam {
     pub arc_instruments_value: Arc<Mutex<Vec<Instrument>>>,
     pub arc_positions_value: Arc<Mutex<Vec<Position>>>,
     pub arc_limits_value: Arc<Mutex<Vec<LimitOrder>>>,
     pub arc_balance_value: Arc<Mutex<BalanceObject>>,
+    pub arc_order_manager: Arc<Mutex<OrderManager>>,
 }
 
-fn main() {
-    print_message(THREAD::MAIN, "Starting Apollo...");
-    dotenv().ok();
+impl ChannelParam {
+    // A worker that panicked while holding a lock leaves it poisoned for everyone else
+    pub fn clear_poison(&self) {
+        self.arc_instruments_value.clear_poison();
+        self.arc_positions_value.clear_poison();
+        self.arc_limits_value.clear_poison();
+        self.arc_balance_value.clear_poison();
+        self.arc_order_manager.clear_poison();
+    }
+}
+
+// Starts one profile's control, buy and sell workers and schedules its cycles.
+// Every thread the profile owns sets it first, so requests, strategy and data files resolve to it
+fn start_profile(profile: &'static AccountProfile, scheduler: &mut Scheduler) -> ProfileWorkers {
+    set_profile(profile);
+    print_message(
+        THREAD::MAIN,
+        &format!(
+            "Starting profile {} - {:?} at {}, data in {}",
+            profile.name,
+            profile.version,
+            profile.get_base_url(),
+            profile.data_dir
+        ),
+    );
 
-    // --------------------- Global Variables --------------------- //
     let trading212_balance_arc: Arc<Mutex<BalanceObject>> =
         Arc::new(Mutex::new(BalanceObject::default()));
 
-    let main_trading212_balance_arc: Arc<Mutex<BalanceObject>> =
-        Arc::clone(&trading212_balance_arc);
-
-    // let b_balance_arc: Arc<Mutex<BalanceObject>> = Arc::clone(&balance_arc);
-    // let s_balance_arc: Arc<Mutex<BalanceObject>> = Arc::clone(&balance_arc);
-
-    //
-    // --------------------- Trading212 Stock List Data Collection Thread --------------------- //
-    print_message(THREAD::MAIN, "Creating 212 stock data collection thread...");
-    let trading212_stock_list_collection_handle: JoinHandle<()> = thread::Builder::new()
-        .name("Trading212_Stock_List_Collection".to_string())
-        .spawn(move || {
-            loop {
-                // Check if current data is already from today
-                print_message(
-                    THREAD::COLLECTION,
-                    "Checking Trading212 Stock List Data Validity...",
-                );
-                if get_instruments_from_file().is_none() {
-                    print_message(
-                        THREAD::COLLECTION,
-                        "Starting Trading212 Stock List Data Collection...",
-                    );
-                    let all_trading212_stocks_data: Vec<Instrument> = get_instruments();
-                    print_message(THREAD::COLLECTION, "Stock List Data Collected.");
-
-                    // Write Full List To "src/data/instruments.json"
-                    write_instruments_to_file(all_trading212_stocks_data); //"src/data/instruments.json"
-                    print_message(THREAD::COLLECTION, "Trading212 Stock List Data Updated.");
-                }
-
-                thread::sleep(Duration::from_secs(60 * 60)); // Every Hour
+    // Orders still open when the last run stopped are followed up from where it left off
+    let order_manager: OrderManager = match load_checkpoint() {
+        Some(checkpoint) => {
+            print_message(
+                THREAD::MAIN,
+                &format!(
+                    "Resuming {} from checkpoint saved {} - stopped while {:?} with {} open orders",
+                    profile.name,
+                    checkpoint.saved_at,
+                    checkpoint.cycle_stage,
+                    checkpoint.order_manager.orders.len()
+                ),
+            );
+            checkpoint.order_manager
+        }
+        None => OrderManager::default(),
+    };
+    set_cycle_stage(CYCLE_STAGE::IDLE);
+
+    let channel_param: ChannelParam = ChannelParam {
+        arc_instruments_value: Arc::new(Mutex::new(Vec::new())),
+        arc_positions_value: Arc::new(Mutex::new(Vec::new())),
+        arc_limits_value: Arc::new(Mutex::new(Vec::new())),
+        arc_balance_value: trading212_balance_arc,
+        arc_order_manager: Arc::new(Mutex::new(order_manager)),
+    };
+
+    // --------------------- Trading Threads --------------------- //
+    let (sched_to_ctrl_tx, sched_to_ctrl_rx) = mpsc::channel::<CYCLE_MESSAGE>();
+    let (ctrl_to_sched_tx, ctrl_to_sched_rx) = mpsc::channel::<CYCLE_MESSAGE>();
+    let (ctrl_to_sell_tx, ctrl_to_sell_rx) = mpsc::channel::<CYCLE_MESSAGE>();
+    let (ctrl_to_buy_tx, ctrl_to_buy_rx) = mpsc::channel::<CYCLE_MESSAGE>();
+    let (sell_to_ctrl_tx, sell_to_ctrl_rx) = mpsc::channel::<CYCLE_MESSAGE>();
+    let (sell_to_buy_tx, sell_to_buy_rx) = mpsc::channel::<CYCLE_MESSAGE>();
+    let (buy_to_sell_tx, buy_to_sell_rx) = mpsc::channel::<CYCLE_MESSAGE>();
+
+    let workers: ProfileWorkers = ProfileWorkers {
+        profile,
+        data: channel_param.clone(),
+        sched_to_ctrl_tx: sched_to_ctrl_tx.clone(),
+    };
+
+    // Supervised - a worker that panics or returns is started again with the same channels
+    print_message(THREAD::MAIN, "Creating control thread...");
+    let control_data: ChannelParam = channel_param.clone();
+    let control_restart_data: ChannelParam = channel_param.clone();
+    let _control_handle: JoinHandle<()> = spawn_supervised(
+        &format!("{}_Control", profile.name),
+        move || {
+            set_profile(profile);
+            start_control(
+                &sched_to_ctrl_rx,
+                &ctrl_to_sched_tx,
+                &ctrl_to_sell_tx,
+                &ctrl_to_buy_tx,
+                &sell_to_ctrl_rx,
+                control_data.clone(),
+            )
+        },
+        move || control_restart_data.clear_poison(),
+    );
+
+    print_message(THREAD::MAIN, "Creating buy thread...");
+    let buy_data: ChannelParam = channel_param.clone();
+    let buy_restart_data: ChannelParam = channel_param.clone();
+    let _buy_handle: JoinHandle<()> = spawn_supervised(
+        &format!("{}_Buy", profile.name),
+        move || {
+            set_profile(profile);
+            start_buying(
+                &ctrl_to_buy_rx,
+                &sell_to_buy_rx,
+                &buy_to_sell_tx,
+                buy_data.clone(),
+            )
+        },
+        move || buy_restart_data.clear_poison(),
+    );
+
+    print_message(THREAD::MAIN, "Creating sell thread...");
+    let sell_data: ChannelParam = channel_param.clone();
+    let sell_restart_data: ChannelParam = channel_param.clone();
+    let _sell_handle: JoinHandle<()> = spawn_supervised(
+        &format!("{}_Sell", profile.name),
+        move || {
+            set_profile(profile);
+            start_selling(
+                &ctrl_to_sell_rx,
+                &sell_to_ctrl_tx,
+                &sell_to_buy_tx,
+                &buy_to_sell_rx,
+                sell_data.clone(),
+            )
+        },
+        move || sell_restart_data.clear_poison(),
+    );
+
+    // Holds the job open until the sell thread ends the cycle, so cycles never overlap
+    // Every message about a cycle carries its id, so replies to an abandoned cycle are ignored
+    let cycle_channels: Mutex<(mpsc::Sender<CYCLE_MESSAGE>, mpsc::Receiver<CYCLE_MESSAGE>)> =
+        Mutex::new((sched_to_ctrl_tx, ctrl_to_sched_rx));
+    let cycle_counter: AtomicU64 = AtomicU64::new(0);
+    scheduler.add_job(
+        &format!("control_refresh_{}", profile.name),
+        &CONTROL_REFRESH_SCHEDULE,
+        move || {
+            let channels = cycle_channels.lock().unwrap();
+            let cycle_id: u64 = cycle_counter.fetch_add(1, Ordering::SeqCst) + 1;
+
+            send_message(
+                &channels.0,
+                CYCLE_MESSAGE::CYCLE_START { id: cycle_id },
+                "Control",
+            );
+            match await_cycle_message(
+                &channels.1,
+                cycle_id,
+                "Control to end the cycle",
+                Some(*CYCLE_TIMEOUT),
+            ) {
+                Some(CYCLE_MESSAGE::CYCLE_COMPLETE { .. }) => {}
+                Some(message) => print_message(
+                    THREAD::MAIN,
+                    &format!("Unexpected {:?} ending cycle {}", message, cycle_id),
+                ),
+                None => print_message(
+                    THREAD::MAIN,
+                    &format!("Cycle {} did not finish in time", cycle_id),
+                ),
             }
-        })
-        .expect("[Main Thread] Failed to spawn Stock List Data Collection thread");
-
-    // --------------------- Trading212 Stock List Filtering Thread --------------------- //
-    print_message(THREAD::MAIN, "Creating 212 stock data filtering thread...");
-    let trading212_stock_list_filtering_handle: JoinHandle<()> = thread::Builder::new()
-        .name("Trading212_Stock_List_Filtering".to_string())
-        .spawn(move || {
-            loop {
-                // Check if current data is already from today
-                print_message(
-                    THREAD::COLLECTION,
-                    "Checking Trading212 Stock List Data Validity...",
-                );
-
-                match get_instruments_from_file() {
-                    None => {
-                        std::thread::sleep(std::time::Duration::new(5, 0));
-                    }
-                    Some(instrument_data) => {
-                        print_message(THREAD::FILTER, "Running Filters...");
-
-                        // Write Filtered List To "src/data/filtered_instruments.json"
-                        let filtered_stocks_data: Vec<Instrument> =
-                            filter_trading212_instruments(filtered_stocks_data.clone());
-                        write_filtered_instruments_to_file(filtered_stocks_data);
-                    }
-                }
-
-                thread::sleep(Duration::from_secs(60 * 60)); // Every Hour
+            print_job_statuses();
+        },
+    );
+
+    workers
+}
+
+fn main() {
+    dotenv().ok();
+
+    // apollo watchlist <command> - manage watchlists without starting the trading threads
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|arg| arg == "watchlist") {
+        run_watchlist_command(&args[2..]);
+        return;
+    }
+
+    // apollo health - for external health checks, exits 1 when a worker is down or the report is stale
+    if args.get(1).is_some_and(|arg| arg == "health") {
+        std::process::exit(match run_health_command() {
+            true => 0,
+            false => 1,
+        });
+    }
+
+    print_message(THREAD::MAIN, "Starting Apollo...");
+
+    if let Err(reason) = validate_profiles() {
+        print_message(
+            THREAD::MAIN,
+            &format!("Invalid profile config - {}", reason),
+        );
+        std::process::exit(1);
+    }
+
+    // --------------------- Profiles --------------------- //
+    let mut scheduler: Scheduler = Scheduler::new();
+
+    let profiles: Vec<ProfileWorkers> = PROFILES
+        .iter()
+        .map(|profile| start_profile(profile, &mut scheduler))
+        .collect();
+
+    let _shutdown_handle: JoinHandle<()> = listen_for_shutdown(profiles);
+    let _health_handle: JoinHandle<()> = start_health_monitor();
+
+    // --------------------- Shared Jobs --------------------- //
+    scheduler.add_job("instruments_refresh", &INSTRUMENTS_REFRESH_SCHEDULE, || {
+        // The instrument list is shared by every profile, so it is fetched with the first one
+        set_profile(&PROFILES[0]);
+        // Check if current data is already from today
+        print_message(
+            THREAD::COLLECTION,
+            "Checking Trading212 Stock List Data Validity...",
+        );
+        if get_instruments_from_file().is_none() {
+            print_message(
+                THREAD::COLLECTION,
+                "Starting Trading212 Stock List Data Collection...",
+            );
+            let all_trading212_stocks_data: Vec<Instrument> = get_instruments();
+            print_message(THREAD::COLLECTION, "Stock List Data Collected.");
+
+            // Record what changed since the last download before it is overwritten
+            if let Some((previous_date, previous_instruments)) =
+                get_previous_instruments_from_file()
+            {
+                log_instrument_diff(diff_instruments(
+                    previous_date,
+                    &previous_instruments,
+                    &all_trading212_stocks_data,
+                ));
+            }
+
+            // Write Full List To "src/data/instruments.json"
+            write_instruments_to_file(all_trading212_stocks_data); //"src/data/instruments.json"
+            print_message(THREAD::COLLECTION, "Trading212 Stock List Data Updated.");
+        }
+
+        // Working schedules map each instrument to its exchange's trading hours
+        if get_exchanges_from_file().is_none() {
+            if let Some(exchanges) = get_exchanges() {
+                write_exchanges_to_file(exchanges); // "src/data/exchanges.json"
+                print_message(THREAD::COLLECTION, "Exchange Schedule Data Updated.");
             }
-        })
-        .expect("[Main Thread] Failed to spawn Stock List Data Collection thread");
+        }
+    });
+
+    scheduler.add_job("screening", &SCREENING_SCHEDULE, || {
+        match get_instruments_from_file() {
+            None => print_message(THREAD::FILTER, "No instruments on file yet. Skipping run"),
+            Some(instrument_data) => {
+                print_message(THREAD::FILTER, "Running Filters...");
+
+                // Write Filtered List To "src/data/filtered_instruments.json"
+                let filtered_stocks_data: Vec<Instrument> =
+                    filter_trading212_instruments(instrument_data);
+                write_filtered_instruments_to_file(filtered_stocks_data);
+            }
+        }
+    });
+
+    let scheduler_handle: JoinHandle<()> = scheduler.start();
 
     // print_message(
     //     THREAD::MAIN,
@@ -181,7 +591,7 @@ fn main() {
     // for current_position in current_positions {
     //     println!("\nPosition: {:?}", &current_position);
     // }
-    _ = trading212_stock_list_collection_handle.join();
+    _ = scheduler_handle.join();
 
     // _ = sell_handle.join();
 }
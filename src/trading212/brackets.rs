@@ -0,0 +1,295 @@
+pub mod brackets {
+    use chrono::Utc;
+    use serde::{Deserialize, Serialize};
+
+    use crate::control::profiles::profiles::{get_profile_path, get_profile_strategy};
+    use crate::helpers::helpers::helpers::{print_message, sleep_thread, THREAD};
+    use crate::helpers::money::money::{decimal_from_f64, Price, Quantity};
+    use crate::trading212::helpers::helpers::{
+        cancel_order, create_limit_order, create_stop_order, TimeValidity, TradeDirection,
+    };
+    use crate::trading212::orders::orders::{OrderEvent, OrderManager, ORDER_STATE};
+    use crate::trading212::types::types::{LimitOrder, Position};
+
+    const BRACKETS_FILE: &str = "brackets.json";
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct BracketLeg {
+        pub order_id: u64,
+        pub price: Price,
+    }
+
+    // A take-profit limit and a protective stop held together over one position.
+    // Trading212 has no OCO orders so Apollo cancels the other leg itself when one fills
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct Bracket {
+        pub ticker: String,
+        pub take_profit_price: Price,
+        pub stop_price: Price,
+        pub take_profit: Option<BracketLeg>, // None until placed, or after the leg expired
+        pub stop: Option<BracketLeg>,
+        pub created_at: i64,
+        #[serde(default)]
+        pub closed: bool, // A leg filled - kept until the position disappears so it is not reopened
+        #[serde(default)]
+        pub average_price: f64, // Of the position when the bracket opened - the sale is logged after it has gone
+        #[serde(default)]
+        pub take_profit_held: bool, // Rejected next to the stop - Apollo watches the price and swaps the legs itself
+    }
+
+    impl Bracket {
+        pub fn get_order_ids(&self) -> Vec<u64> {
+            [&self.take_profit, &self.stop]
+                .into_iter()
+                .flatten()
+                .map(|leg| leg.order_id)
+                .collect()
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Default)]
+    pub struct BracketBook {
+        pub brackets: Vec<Bracket>,
+    }
+
+    pub fn load_brackets() -> BracketBook {
+        match std::fs::read_to_string(get_profile_path(BRACKETS_FILE)).ok() {
+            Some(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+            None => BracketBook::default(),
+        }
+    }
+
+    pub fn save_brackets(book: &BracketBook) {
+        let contents: String =
+            serde_json::to_string_pretty(book).expect("Bracket serialization failed");
+
+        if std::fs::write(get_profile_path(BRACKETS_FILE), contents).is_err() {
+            print_message(THREAD::FILE, "Failed to write brackets");
+        }
+    }
+
+    // Clears the leg once its cancel goes through. A leg that failed to cancel is kept so the
+    // next sync tries again rather than leaving a live stop behind at the broker.
+    // Returns true when no leg is left
+    fn cancel_leg(leg: &mut Option<BracketLeg>) -> bool {
+        if let Some(live_leg) = leg {
+            let cancelled: bool = cancel_order(live_leg.order_id).is_some();
+            sleep_thread(2);
+            if !cancelled {
+                println!(
+                    "ST: Failed to cancel bracket leg {}. Retrying next sync.",
+                    live_leg.order_id
+                );
+                return false;
+            }
+            *leg = None;
+        }
+        true
+    }
+
+    // perc_change is a fraction like SELL_PERCENT_UP/DOWN, not a percent
+    fn price_from_average(average_price: f64, perc_change: f64) -> Price {
+        Price(decimal_from_f64(average_price) * decimal_from_f64(1.0 + perc_change))
+    }
+
+    impl BracketBook {
+        pub fn get_bracket(&self, ticker: &String) -> Option<&Bracket> {
+            self.brackets
+                .iter()
+                .find(|bracket| bracket.ticker.eq_ignore_ascii_case(ticker))
+        }
+
+        pub fn has_bracket(&self, ticker: &String) -> bool {
+            self.get_bracket(ticker).is_some()
+        }
+
+        // Every live leg - these must survive the sell thread's order cancelling
+        pub fn get_order_ids(&self) -> Vec<u64> {
+            self.brackets
+                .iter()
+                .flat_map(|bracket| bracket.get_order_ids())
+                .collect()
+        }
+
+        // A filled leg closes the bracket and cancels its sibling.
+        // A leg that ended any other way is cleared so sync_positions can place it again
+        pub fn apply_events(&mut self, events: &Vec<OrderEvent>) {
+            for event in events {
+                if !event.state.is_terminal() {
+                    continue;
+                }
+
+                let bracket: &mut Bracket = match self
+                    .brackets
+                    .iter_mut()
+                    .find(|bracket| bracket.get_order_ids().contains(&event.order_id))
+                {
+                    Some(bracket) => bracket,
+                    None => continue,
+                };
+
+                let is_take_profit: bool = bracket
+                    .take_profit
+                    .as_ref()
+                    .is_some_and(|leg| leg.order_id == event.order_id);
+
+                match (event.state, is_take_profit) {
+                    (ORDER_STATE::FILLED, true) => {
+                        println!(
+                            "ST: {} take profit filled. Cancelling stop.",
+                            bracket.ticker
+                        );
+                        bracket.take_profit = None;
+                        cancel_leg(&mut bracket.stop);
+                        bracket.closed = true;
+                    }
+                    (ORDER_STATE::FILLED, false) => {
+                        println!(
+                            "ST: {} stopped out. Cancelling take profit.",
+                            bracket.ticker
+                        );
+                        bracket.stop = None;
+                        cancel_leg(&mut bracket.take_profit);
+                        bracket.closed = true;
+                    }
+                    (_, true) => {
+                        // A part filled take profit leaves the stop oversized - replace both
+                        bracket.take_profit = None;
+                        if !event.filled_quantity.is_zero() {
+                            cancel_leg(&mut bracket.stop);
+                        }
+                    }
+                    (_, false) => {
+                        bracket.stop = None;
+                        if !event.filled_quantity.is_zero() {
+                            cancel_leg(&mut bracket.take_profit);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Opens brackets for new positions, replaces missing legs and closes brackets whose position has gone
+        pub fn sync_positions(
+            &mut self,
+            positions: &Vec<Position>,
+            order_manager: &mut OrderManager,
+        ) {
+            // Closed brackets still holding a leg whose cancel failed try again here. A bracket
+            // whose position has gone is only dropped once both its legs are cancelled
+            self.brackets.retain_mut(|bracket| {
+                let held: bool = positions
+                    .iter()
+                    .any(|position| position.ticker.eq_ignore_ascii_case(&bracket.ticker));
+                if !held && !bracket.closed {
+                    println!("ST: {} no longer held. Closing bracket.", bracket.ticker);
+                    bracket.closed = true;
+                }
+                if !bracket.closed {
+                    return true;
+                }
+                let take_profit_cancelled: bool = cancel_leg(&mut bracket.take_profit);
+                let stop_cancelled: bool = cancel_leg(&mut bracket.stop);
+                held || !(take_profit_cancelled && stop_cancelled)
+            });
+
+            for position in positions {
+                if !self.has_bracket(&position.ticker) {
+                    self.brackets.push(Bracket {
+                        ticker: position.ticker.clone(),
+                        take_profit_price: price_from_average(
+                            position.average_price,
+                            get_profile_strategy().sell_percent_up,
+                        ),
+                        stop_price: price_from_average(
+                            position.average_price,
+                            get_profile_strategy().sell_percent_down,
+                        ),
+                        take_profit: None,
+                        stop: None,
+                        created_at: Utc::now().timestamp(),
+                        closed: false,
+                        average_price: position.average_price,
+                        take_profit_held: false,
+                    });
+                }
+
+                let bracket: &mut Bracket = self
+                    .brackets
+                    .iter_mut()
+                    .find(|bracket| bracket.ticker.eq_ignore_ascii_case(&position.ticker))
+                    .unwrap();
+
+                if bracket.closed {
+                    continue;
+                }
+
+                // Both order paths round this down to the instrument's precision
+                let sell_quantity: Quantity = Quantity::from_f64(position.quantity).negate();
+
+                // The stop goes first as it is the leg protecting the position.
+                // A live take profit already holds the shares, so the stop waits for it to end
+                if bracket.stop.is_none() && bracket.take_profit.is_none() {
+                    let order: Option<LimitOrder> = create_stop_order(
+                        position.ticker.clone(),
+                        bracket.stop_price.clone(),
+                        sell_quantity.clone(),
+                        TimeValidity::GTC,
+                    );
+                    bracket.stop = order.map(|order| {
+                        order_manager.track_limit_order(&order);
+                        BracketLeg {
+                            order_id: order.id,
+                            price: bracket.stop_price.clone(),
+                        }
+                    });
+                    sleep_thread(2);
+                }
+
+                if bracket.take_profit.is_some() {
+                    continue;
+                }
+
+                if bracket.take_profit_held {
+                    if position.current_price < bracket.take_profit_price.to_f64() {
+                        continue;
+                    }
+                    println!(
+                        "ST: {} reached its take profit. Swapping the stop for a limit sell.",
+                        bracket.ticker
+                    );
+                    if !cancel_leg(&mut bracket.stop) {
+                        continue;
+                    }
+                }
+
+                // Limit orders are DAY only, so this leg is replaced each trading day
+                let order: Option<LimitOrder> = create_limit_order(
+                    position.ticker.clone(),
+                    bracket.take_profit_price.clone(),
+                    sell_quantity,
+                    TradeDirection::SELL,
+                );
+                match order {
+                    Some(order) => {
+                        order_manager.track_limit_order(&order);
+                        bracket.take_profit = Some(BracketLeg {
+                            order_id: order.id,
+                            price: bracket.take_profit_price.clone(),
+                        });
+                    }
+                    None if bracket.stop.is_some() => {
+                        // Trading212 will not hold two sells over the same shares
+                        println!(
+                            "ST: {} take profit rejected next to the stop. Placing it once the price gets there.",
+                            bracket.ticker
+                        );
+                        bracket.take_profit_held = true;
+                    }
+                    None => {}
+                }
+                sleep_thread(2);
+            }
+        }
+    }
+}
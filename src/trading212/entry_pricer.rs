@@ -0,0 +1,343 @@
+pub mod entry_pricer {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use bigdecimal::{BigDecimal, RoundingMode};
+    use chrono::Utc;
+    use serde::{Deserialize, Serialize};
+
+    use crate::control::profiles::profiles::{get_profile_path, get_profile_strategy};
+    use crate::finnhub::utils::helpers::FHStockData;
+    use crate::helpers::helpers::helpers::{print_message, sleep_thread, THREAD};
+    use crate::helpers::money::money::{decimal_from_f64, tick_size, Price, Quantity};
+    use crate::helpers::types::types::FullCompanyInfo;
+    use crate::trading212::helpers::helpers::{
+        cancel_order, create_limit_order, create_market_order, get_single_order, TradeDirection,
+    };
+    use crate::trading212::orders::orders::{HistoryCursor, OrderEvent, OrderManager, ORDER_STATE};
+    use crate::trading212::types::types::{LimitOrder, MarketOrder};
+    use crate::{ENTRY_MODE, LIMIT_WAIT_TIME, PATIENT_LADDER_START_BPS, PATIENT_LADDER_STEPS};
+
+    const ENTRY_STATS_FILE: &str = "entry_stats.json";
+    // Long past any entry's last rung, so a filled entry can no longer be credited twice
+    const FILLED_ENTRY_TTL: i64 = 86_400;
+
+    #[derive(Debug, Serialize, Deserialize, Default, Clone)]
+    pub struct ModeStats {
+        pub placed: u32,
+        pub filled: u32, // Entries with any fill, partial included
+    }
+
+    impl ModeStats {
+        pub fn get_fill_rate(&self) -> f64 {
+            match self.placed {
+                0 => 0.0,
+                placed => self.filled as f64 / placed as f64,
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Default)]
+    pub struct EntryStats {
+        pub modes: HashMap<String, ModeStats>,
+        pub pending: HashMap<u64, String>, // Open entry order id -> mode that placed it
+        #[serde(default)]
+        pub entry_ids: HashMap<u64, u64>, // Ladder rung order id -> id of the entry's first rung
+        #[serde(default)]
+        pub filled_entries: HashMap<u64, i64>, // Entry id -> when its first fill was counted
+    }
+
+    fn load_entry_stats() -> EntryStats {
+        match std::fs::read_to_string(get_profile_path(ENTRY_STATS_FILE)).ok() {
+            Some(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+            None => EntryStats::default(),
+        }
+    }
+
+    fn save_entry_stats(stats: &EntryStats) {
+        let contents: String =
+            serde_json::to_string_pretty(stats).expect("Entry stats serialization failed");
+
+        if std::fs::write(get_profile_path(ENTRY_STATS_FILE), contents).is_err() {
+            print_message(THREAD::FILE, "Failed to write entry stats");
+        }
+    }
+
+    // Counts a new entry. Ladder rungs after the first are added to pending under the first rung's id
+    fn record_entry(order_id: u64, mode: &String, entry_id: Option<u64>) {
+        let mut stats: EntryStats = load_entry_stats();
+        match entry_id {
+            Some(entry_id) => {
+                stats.entry_ids.insert(order_id, entry_id);
+            }
+            None => stats.modes.entry(mode.clone()).or_default().placed += 1,
+        }
+        stats.pending.insert(order_id, mode.clone());
+        save_entry_stats(&stats);
+    }
+
+    // Called with every batch of order events - credits fills back to the mode that priced them
+    pub fn record_entry_outcomes(events: &Vec<OrderEvent>) {
+        let mut stats: EntryStats = load_entry_stats();
+        let mut changed: bool = false;
+
+        for event in events.iter().filter(|event| event.state.is_terminal()) {
+            let mode: String = match stats.pending.remove(&event.order_id) {
+                Some(mode) => mode,
+                None => continue,
+            };
+            changed = true;
+            let entry_id: u64 = stats
+                .entry_ids
+                .remove(&event.order_id)
+                .unwrap_or(event.order_id);
+
+            let filled: bool = match event.state {
+                ORDER_STATE::FILLED => true,
+                _ => !event.filled_quantity.is_zero(),
+            };
+            // Several rungs of one ladder can fill, but the entry only counts once
+            if filled && !stats.filled_entries.contains_key(&entry_id) {
+                stats.modes.entry(mode).or_default().filled += 1;
+                stats
+                    .filled_entries
+                    .insert(entry_id, Utc::now().timestamp());
+            }
+        }
+
+        if changed {
+            let now: i64 = Utc::now().timestamp();
+            stats
+                .filled_entries
+                .retain(|_, filled_at| now - *filled_at < FILLED_ENTRY_TTL);
+            save_entry_stats(&stats);
+        }
+    }
+
+    pub fn print_fill_rates() {
+        for (mode, mode_stats) in load_entry_stats().modes {
+            println!(
+                "BT: {} fill rate {:.1}% ({}/{})",
+                mode,
+                mode_stats.get_fill_rate() * 100.0,
+                mode_stats.filled,
+                mode_stats.placed
+            );
+        }
+    }
+
+    fn bps_below(price: f64, bps: u32) -> Price {
+        let factor: BigDecimal =
+            decimal_from_f64(1.0) - decimal_from_f64(bps as f64) / BigDecimal::from(10_000);
+        Price(decimal_from_f64(price) * factor)
+    }
+
+    // Filled quantity of a rung once it is cancelled. The history can lag the cancel, so it is
+    // asked a few times before giving up
+    fn get_final_filled(order_id: u64) -> Option<Quantity> {
+        for _ in 0..3 {
+            let filled: Option<Quantity> = HistoryCursor::default()
+                .find(order_id)
+                .map(|historical| Quantity::from_f64(historical.filled_quantity).abs());
+            if filled.is_some() {
+                return filled;
+            }
+            sleep_thread(2);
+        }
+        None
+    }
+
+    pub struct EntryPricer {
+        pub mode: &'static ENTRY_MODE,
+        pub mode_name: String,
+    }
+
+    impl EntryPricer {
+        pub fn new() -> EntryPricer {
+            EntryPricer {
+                mode: get_profile_strategy().entry_pricing,
+                mode_name: format!("{:?}", get_profile_strategy().entry_pricing),
+            }
+        }
+
+        // Limit price for the limit based modes. None for MARKET
+        pub fn get_limit_price(&self, quote: &FHStockData) -> Option<Price> {
+            match self.mode {
+                ENTRY_MODE::MARKET => None,
+                ENTRY_MODE::AT_QUOTE => Some(Price::from_f64(quote.current_price)),
+                ENTRY_MODE::BELOW_QUOTE(bps) => Some(bps_below(quote.current_price, *bps)),
+                ENTRY_MODE::MID_HIGH_LOW => {
+                    if quote.todays_high <= 0.0 || quote.todays_low <= 0.0 {
+                        return Some(Price::from_f64(quote.current_price));
+                    }
+                    // Never above the current quote - that would be worse than AT_QUOTE
+                    let mid: f64 = (quote.todays_high + quote.todays_low) / 2.0;
+                    Some(Price::from_f64(mid.min(quote.current_price)))
+                }
+                ENTRY_MODE::PATIENT_LADDER => {
+                    Some(bps_below(quote.current_price, *PATIENT_LADDER_START_BPS))
+                }
+            }
+        }
+
+        fn place_limit(
+            &self,
+            company: &FullCompanyInfo,
+            price: &Price,
+            quantity: &Quantity,
+            order_manager: &Arc<Mutex<OrderManager>>,
+            entry_id: Option<u64>, // None for the first order of an entry
+        ) -> Option<LimitOrder> {
+            let order: LimitOrder = create_limit_order(
+                company.instrument.ticker.clone(),
+                price.clone(),
+                quantity.clone(),
+                TradeDirection::BUY,
+            )?;
+            order_manager.lock().unwrap().track_limit_order(&order);
+            record_entry(order.id, &self.mode_name, entry_id);
+            Some(order)
+        }
+
+        // Places the entry for a company. Returns the id of the order left working at the broker
+        pub fn enter(
+            &self,
+            company: &FullCompanyInfo,
+            quantity: Quantity,
+            order_manager: &Arc<Mutex<OrderManager>>,
+        ) -> Option<u64> {
+            let ticker: &String = &company.instrument.ticker;
+
+            let limit_price: Price = match self.get_limit_price(&company.company_stock_quote) {
+                Some(limit_price) => {
+                    limit_price.round_to_tick(&tick_size(&company.instrument), RoundingMode::Down)
+                }
+                None => {
+                    println!("BT: Creating market buy for {}", ticker);
+                    let order: Option<MarketOrder> = create_market_order(ticker.clone(), quantity);
+                    return order.map(|order| {
+                        order_manager.lock().unwrap().track_market_order(&order);
+                        record_entry(order.id, &self.mode_name, None);
+                        order.id
+                    });
+                }
+            };
+
+            println!(
+                "BT: Creating {} buy limit order for {} at {}",
+                self.mode_name, ticker, limit_price
+            );
+
+            let order: LimitOrder =
+                match self.place_limit(company, &limit_price, &quantity, order_manager, None) {
+                    Some(order) => order,
+                    None => {
+                        println!("BT: Failed to create buy limit order. Retrying...\n");
+                        sleep_thread(5);
+
+                        // Retry one tick higher
+                        let retry_price: Price =
+                            Price(limit_price.0.clone() + tick_size(&company.instrument));
+                        match self.place_limit(
+                            company,
+                            &retry_price,
+                            &quantity,
+                            order_manager,
+                            None,
+                        ) {
+                            Some(order) => order,
+                            None => {
+                                println!("BT: Failed second buy attempt. Skipping...\n");
+                                return None;
+                            }
+                        }
+                    }
+                };
+
+            match self.mode {
+                ENTRY_MODE::PATIENT_LADDER => {
+                    Some(self.work_ladder(company, order, quantity, order_manager))
+                }
+                _ => Some(order.id),
+            }
+        }
+
+        // Steps the limit from its starting discount up to the quote across LIMIT_WAIT_TIME.
+        // Returns the id of the last rung placed
+        fn work_ladder(
+            &self,
+            company: &FullCompanyInfo,
+            mut order: LimitOrder,
+            quantity: Quantity,
+            order_manager: &Arc<Mutex<OrderManager>>,
+        ) -> u64 {
+            let steps: u32 = (*PATIENT_LADDER_STEPS).max(1);
+            let step_wait: u64 = (*LIMIT_WAIT_TIME / steps as u64).max(1);
+            let quote: f64 = company.company_stock_quote.current_price;
+            let entry_id: u64 = order.id;
+            // Filled by the rungs already cancelled
+            let mut total_filled: Quantity = Quantity::default();
+
+            for step in 1..=steps {
+                sleep_thread(step_wait);
+
+                let filled: Quantity = match get_single_order(order.id) {
+                    Some(active) => active.filled_quantity.abs(),
+                    // Gone from the active list - filled or cancelled, the order manager will tell
+                    None => return order.id,
+                };
+
+                if !Quantity(&quantity.0 - &total_filled.0 - &filled.0).is_buy() {
+                    return order.id;
+                }
+
+                if cancel_order(order.id).is_none() {
+                    println!("BT: Could not cancel ladder rung for {}.", order.ticker);
+                    return order.id;
+                }
+                sleep_thread(2);
+
+                // The rung can fill between the read above and the cancel, so the next rung is
+                // sized from what the cancelled one finally filled
+                let final_filled: Quantity = match get_final_filled(order.id) {
+                    Some(final_filled) => final_filled,
+                    None => {
+                        println!(
+                            "BT: Could not confirm what the {} rung filled. Stopping the ladder.",
+                            order.ticker
+                        );
+                        return order.id;
+                    }
+                };
+                total_filled = Quantity(&total_filled.0 + &final_filled.0);
+
+                let remaining: Quantity = Quantity(&quantity.0 - &total_filled.0);
+                if !remaining.is_buy() {
+                    return order.id;
+                }
+
+                // Linearly from the starting discount to zero on the final step
+                let bps: u32 = *PATIENT_LADDER_START_BPS * (steps - step) / steps;
+                let price: Price = bps_below(quote, bps)
+                    .round_to_tick(&tick_size(&company.instrument), RoundingMode::Down);
+                println!(
+                    "BT: Repricing {} to {} ({} bps below quote)",
+                    order.ticker, price, bps
+                );
+
+                order = match self.place_limit(
+                    company,
+                    &price,
+                    &remaining,
+                    order_manager,
+                    Some(entry_id),
+                ) {
+                    Some(order) => order,
+                    // The last rung was cancelled and nothing replaced it
+                    None => return order.id,
+                };
+            }
+            order.id
+        }
+    }
+}
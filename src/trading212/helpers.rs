@@ -1,27 +1,38 @@
 pub mod helpers {
 
+    use bigdecimal::{BigDecimal, RoundingMode};
     use serde::{Deserialize, Serialize};
 
     use std::{
-        env,
         sync::{Arc, Mutex},
         time::SystemTime,
     };
 
     use crate::{
+        control::profiles::profiles::{get_profile, get_profile_strategy},
         file_control::types::types::SaleResult,
         helpers::{
+            exclusions::exclusions::get_ticker_exclusion_reason,
+            fx::fx::{get_fx_fee_rate, price_in_account_currency, FxRates},
             helpers::helpers::{
-                calc_perc_change, calculate_amount_spent_per_ticker, chop_two_after_dec,
-                print_message, round_down, shuffle_positions, sleep_thread, THREAD,
+                calc_perc_change, chop_two_after_dec, print_message, round_down, shuffle_positions,
+                sleep_thread, THREAD,
             },
+            listings::listings::get_listed_instrument,
+            money::money::{
+                decimal_from_f64, quantity_precision, tick_size, Money, Price, Quantity,
+            },
+            sizing::sizing::get_position_budget,
             types::types::FullCompanyInfo,
         },
+        trading212::registry::registry::{
+            is_protected_ticker, is_registered_order, register_order,
+        },
         trading212::types::types::{
-            BalanceObject, HistoricalOrder, Instrument, LimitOrder, MarketOrder, Position,
-            SystemLimitOrder, MOVEMENT_DIRECTION,
+            BalanceObject, Exchange, HistoricalOrder, HistoryPage, Instrument, LimitOrder,
+            MarketOrder, Position, SystemLimitOrder, MOVEMENT_DIRECTION,
         },
-        SELL_PERCENT_DOWN, SELL_PERCENT_UP, STOCK_VERSION, VERSION,
+        DEFAULT_TICK_SIZE,
     };
 
     use reqwest::{header, Body, Client, RequestBuilder, Response};
@@ -31,10 +42,7 @@ pub mod helpers {
     ////////////////////////// PIES //////////////////////////////////
 
     pub fn get_single_pie(pie_id: i32) -> Value {
-        let query: String = format!(
-            "https://demo.trading212.com/api/v0/equity/pies/{}",
-            pie_id.to_string(),
-        );
+        let query: String = get_profile().get_api_url(&format!("equity/pies/{}", pie_id));
 
         let raw_data: Option<serde_json::Value> = make_request(query);
         let data: Value = raw_data.unwrap();
@@ -44,10 +52,7 @@ pub mod helpers {
     }
 
     pub fn get_all_pies() -> Vec<Value> {
-        let query: String = match *STOCK_VERSION {
-            VERSION::DEMO => String::from("https://demo.trading212.com/api/v0/equity/pies"),
-            VERSION::LIVE => String::from("https://live.trading212.com/api/v0/equity/pies"),
-        };
+        let query: String = get_profile().get_api_url("equity/pies");
 
         let raw_data: Option<serde_json::Value> = make_request(query);
         let data: Vec<Value> = raw_data.unwrap().as_array().unwrap().to_owned();
@@ -61,10 +66,7 @@ pub mod helpers {
     pub fn get_account_balance() -> Result<BalanceObject, serde_json::Error> {
         println!("\nCT: Fetching account data...");
 
-        let query: String = match *STOCK_VERSION {
-            VERSION::DEMO => String::from("https://demo.trading212.com/api/v0/equity/account/cash"),
-            VERSION::LIVE => String::from("https://live.trading212.com/api/v0/equity/account/cash"),
-        };
+        let query: String = get_profile().get_api_url("equity/account/cash");
 
         let raw_data: Option<serde_json::Value> = make_request(query);
 
@@ -78,10 +80,7 @@ pub mod helpers {
     pub fn update_account_balance(balance_arc: Arc<Mutex<BalanceObject>>) {
         print_message(THREAD::COLLECTION, "Fetching Trading212 Balance data...");
 
-        let query: String = match *STOCK_VERSION {
-            VERSION::DEMO => String::from("https://demo.trading212.com/api/v0/equity/account/cash"),
-            VERSION::LIVE => String::from("https://live.trading212.com/api/v0/equity/account/cash"),
-        };
+        let query: String = get_profile().get_api_url("equity/account/cash");
 
         let raw_data: Option<serde_json::Value> = make_request(query);
 
@@ -101,7 +100,7 @@ pub mod helpers {
     ////////////////////////// DIVIDENDS //////////////////////////////////
 
     pub fn get_paid_dividends() -> Value {
-        let query: String = String::from("https://demo.trading212.com/api/v0/history/dividends");
+        let query: String = get_profile().get_api_url("history/dividends");
 
         let raw_data: Option<serde_json::Value> = make_request(query);
         let data: Value = raw_data.unwrap();
@@ -113,7 +112,7 @@ pub mod helpers {
     ////////////////////////// TRANSACTIONS //////////////////////////////////
 
     pub fn get_transaction_list() -> Value {
-        let query: String = String::from("https://demo.trading212.com/api/v0/history/transactions");
+        let query: String = get_profile().get_api_url("history/transactions");
 
         let raw_data: Option<serde_json::Value> = make_request(query);
 
@@ -126,10 +125,7 @@ pub mod helpers {
     ////////////////////////// POSITIONS //////////////////////////////////
 
     pub fn get_all_positions() -> Option<Vec<Position>> {
-        let query: &str = match *STOCK_VERSION {
-            VERSION::DEMO => "https://demo.trading212.com/api/v0/equity/portfolio",
-            VERSION::LIVE => "https://live.trading212.com/api/v0/equity/portfolio",
-        };
+        let query: String = get_profile().get_api_url("equity/portfolio");
 
         let raw_data: Option<serde_json::Value> = make_request(query.to_owned());
 
@@ -154,16 +150,7 @@ pub mod helpers {
     }
 
     pub fn get_single_position(ticker: &str) -> Option<Position> {
-        let query: String = match *STOCK_VERSION {
-            VERSION::DEMO => format!(
-                "https://demo.trading212.com/api/v0/equity/portfolio/{}",
-                ticker
-            ),
-            VERSION::LIVE => format!(
-                "https://live.trading212.com/api/v0/equity/portfolio/{}",
-                ticker
-            ),
-        };
+        let query: String = get_profile().get_api_url(&format!("equity/portfolio/{}", ticker));
 
         let raw_data: Option<serde_json::Value> = make_request(query);
         if raw_data.is_none() {
@@ -179,16 +166,18 @@ pub mod helpers {
     ////////////////////////// ORDERS //////////////////////////////////
 
     #[tokio::main]
-    pub async fn create_market_order(ticker: String, quantity: f64) -> Option<MarketOrder> {
-        let auth_token: String = match *STOCK_VERSION {
-            VERSION::DEMO => env::var("DEMO_API_KEY").expect("DEMO_API_KEY must be set"),
-            VERSION::LIVE => env::var("LIVE_API_KEY").expect("LIVE_API_KEY must be set"),
-        };
+    pub async fn create_market_order(ticker: String, quantity: Quantity) -> Option<MarketOrder> {
+        let auth_token: String = get_profile().get_api_key();
 
-        let endpoint: &str = match *STOCK_VERSION {
-            VERSION::DEMO => "https://demo.trading212.com/api/v0/equity/orders/market",
-            VERSION::LIVE => "https://live.trading212.com/api/v0/equity/orders/market",
-        };
+        let endpoint: String = get_profile().get_api_url("equity/orders/market");
+
+        if is_protected_ticker(&ticker) {
+            println!(
+                "ST: {} is protected. Ignoring market order request.",
+                ticker
+            );
+            return None;
+        }
 
         let json_body: Value = json!({
             "quantity": quantity,
@@ -213,10 +202,10 @@ pub mod helpers {
             let result: Result<Value, serde_json::Error> = serde_json::from_str(&body);
             println!("ST: Market order successful.");
 
-            let trade_type: &str = match quantity {
-                x if x > 0.0 => "Bought",
-                x if x < 0.0 => "Sold",
-                _ => "Held",
+            let trade_type: &str = match (quantity.is_buy(), quantity.is_zero()) {
+                (true, _) => "Bought",
+                (false, false) => "Sold",
+                (false, true) => "Held",
             };
 
             match result {
@@ -230,6 +219,7 @@ pub mod helpers {
 
                     match res {
                         Ok(market_orders) => {
+                            register_order(market_orders.id, &market_orders.ticker);
                             return Some(market_orders);
                         }
                         Err(e) => {
@@ -257,27 +247,123 @@ pub mod helpers {
         SELL,
     }
 
+    // Posts an order body to equity/orders/<order_path>. Every limit style endpoint takes and
+    // returns the same order shape, and anything placed here is Apollo's to cancel later
+    async fn post_order(order_path: &str, json_body: Value) -> Option<LimitOrder> {
+        let auth_token: String = get_profile().get_api_key();
+
+        let endpoint: String = get_profile().get_api_url(&format!("equity/orders/{}", order_path));
+
+        let is_buy: bool = json_body["quantity"].as_f64().unwrap_or(0.0) > 0.0;
+        let thread_name: &str = match is_buy {
+            true => "BT",
+            false => "ST",
+        };
+        let trade_type: &str = match is_buy {
+            true => "buy",
+            false => "sell",
+        };
+
+        let client: Client = Client::new();
+
+        let resp = client
+            .post(&endpoint)
+            .header(header::AUTHORIZATION, auth_token)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::to_string(&json_body).unwrap()))
+            .send()
+            .await;
+
+        if resp.is_err() {
+            println!("{}: Error in {} order response.\n", thread_name, order_path);
+            return None;
+        }
+
+        let response: Response = resp.unwrap();
+
+        if !response.status().is_success() {
+            println!(
+                "{}: {} order failed. Code: {:?}",
+                thread_name,
+                order_path,
+                &response.status()
+            );
+            return None;
+        }
+
+        let body: String = response.text().await.unwrap();
+        match serde_json::from_str::<LimitOrder>(&body) {
+            Ok(order) => {
+                register_order(order.id, &order.ticker);
+                println!(
+                    "{}: Created {} order to {} {} shares of {}",
+                    thread_name,
+                    order_path,
+                    trade_type,
+                    order.quantity.abs(),
+                    order.ticker
+                );
+                Some(order)
+            }
+            Err(e) => {
+                println!(
+                    "{}: Failed to deserialize {} order: {:?}",
+                    thread_name, order_path, e
+                );
+                None
+            }
+        }
+    }
+
+    // Snaps an order to the listed instrument's tick and quantity precision. Quantities round
+    // towards zero so an order never asks for more than is held or affordable
+    fn round_order(
+        ticker: &String,
+        price: &Price,
+        quantity: &Quantity,
+        price_mode: RoundingMode,
+    ) -> (Price, Quantity) {
+        match get_listed_instrument(ticker) {
+            Some(instrument) => (
+                price.round_to_tick(&tick_size(&instrument), price_mode),
+                quantity.round_to_precision(quantity_precision(&instrument)),
+            ),
+            None => (
+                price.round_to_tick(&decimal_from_f64(*DEFAULT_TICK_SIZE), price_mode),
+                quantity.clone(),
+            ),
+        }
+    }
+
     #[tokio::main]
     pub async fn create_limit_order(
         ticker: String,
-        price: f64,
-        quantity: f64,
+        price: Price,
+        quantity: Quantity,
         trade_direction: TradeDirection,
     ) -> Option<LimitOrder> {
-        let auth_token: String = match *STOCK_VERSION {
-            VERSION::DEMO => env::var("DEMO_API_KEY").expect("DEMO_API_KEY must be set"),
-            VERSION::LIVE => env::var("LIVE_API_KEY").expect("LIVE_API_KEY must be set"),
-        };
+        if let Some(reason) = get_ticker_exclusion_reason(&ticker) {
+            println!("Ignoring limit request for {} - {}.", ticker, reason);
+            return None;
+        }
 
-        if ticker.to_ascii_lowercase().contains("vusa") {
-            println!("Ignoring VUSA limit request.");
+        if is_protected_ticker(&ticker) {
+            println!("{} is protected. Ignoring limit request.", ticker);
             return None;
         }
 
-        let endpoint: &str = match *STOCK_VERSION {
-            VERSION::DEMO => "https://demo.trading212.com/api/v0/equity/orders/limit",
-            VERSION::LIVE => "https://live.trading212.com/api/v0/equity/orders/limit",
+        // Buys round down and sells round up so the tick never works against us
+        let rounding_mode: RoundingMode = match trade_direction {
+            TradeDirection::BUY => RoundingMode::Down,
+            TradeDirection::SELL => RoundingMode::Up,
         };
+        let (price, quantity): (Price, Quantity) =
+            round_order(&ticker, &price, &quantity, rounding_mode);
+
+        if quantity.is_zero() {
+            println!("{} rounds to zero shares. Ignoring limit request.", ticker);
+            return None;
+        }
 
         let json_body: Value = json!({
             "quantity": quantity,
@@ -286,101 +372,122 @@ pub mod helpers {
             "timeValidity": "DAY"
         });
 
-        let client: Client = Client::new();
+        post_order("limit", json_body).await
+    }
 
-        let thread_name: &str = match trade_direction {
-            TradeDirection::BUY => "BT",
-            TradeDirection::SELL => "ST",
-        };
+    pub enum TimeValidity {
+        DAY, // Expires at the end of the trading day
+        GTC, // Good till cancelled
+    }
 
-        // println!("\n{}: Limit Order Payload: {:?}", thread_name, &json_body);
+    impl TimeValidity {
+        fn as_str(&self) -> &'static str {
+            match self {
+                TimeValidity::DAY => "DAY",
+                TimeValidity::GTC => "GOOD_TILL_CANCEL",
+            }
+        }
+    }
 
-        let resp = client
-            .post(endpoint)
-            .header(header::AUTHORIZATION, auth_token)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(Body::from(serde_json::to_string(&json_body).unwrap()))
-            .send()
-            .await;
+    // Market order triggered once the price reaches stop_price. Negative quantity for sells
+    #[tokio::main]
+    pub async fn create_stop_order(
+        ticker: String,
+        stop_price: Price,
+        quantity: Quantity,
+        time_validity: TimeValidity,
+    ) -> Option<LimitOrder> {
+        if let Some(reason) = get_ticker_exclusion_reason(&ticker) {
+            println!("Ignoring stop request for {} - {}.", ticker, reason);
+            return None;
+        }
 
-        if resp.is_err() {
-            println!("{}: Error in limit order response.\n", thread_name);
+        if is_protected_ticker(&ticker) {
+            println!("{} is protected. Ignoring stop request.", ticker);
             return None;
         }
 
-        let response: Response = resp.unwrap();
+        // A sell stop rounded up triggers a tick early rather than a tick late
+        let rounding_mode: RoundingMode = match quantity.is_buy() {
+            true => RoundingMode::Down,
+            false => RoundingMode::Up,
+        };
+        let (stop_price, quantity): (Price, Quantity) =
+            round_order(&ticker, &stop_price, &quantity, rounding_mode);
 
-        // println!("\n{}: Pure Res: {:?}\n", thread_name, response);
+        if quantity.is_zero() {
+            println!("{} rounds to zero shares. Ignoring stop request.", ticker);
+            return None;
+        }
 
-        if response.status().is_success() {
-            let body: String = response.text().await.unwrap();
-            let result: Result<Value, serde_json::Error> = serde_json::from_str(&body);
-            // println!("{}: Limit order creation successful.", thread_name);
+        let json_body: Value = json!({
+            "quantity": quantity,
+            "ticker": ticker,
+            "stopPrice": stop_price,
+            "timeValidity": time_validity.as_str()
+        });
 
-            let trade_type: &str = match trade_direction {
-                TradeDirection::BUY => "buy",
-                TradeDirection::SELL => "sell",
-            };
+        post_order("stop", json_body).await
+    }
 
-            match result {
-                Ok(json) => {
-                    // println!("Order JSON: {:?}", &json);
-                    let content: String = format!(
-                        "{}: Created limit order to {} {} shares of {} at {}",
-                        thread_name,
-                        trade_type,
-                        quantity.abs(),
-                        ticker,
-                        price
-                    );
-                    println!("{}", &content);
-
-                    let res: Result<LimitOrder, serde_json::Error> = serde_json::from_value(json);
+    // Limit order placed once the price reaches stop_price. Negative quantity for sells
+    #[tokio::main]
+    pub async fn create_stop_limit_order(
+        ticker: String,
+        stop_price: Price,
+        limit_price: Price,
+        quantity: Quantity,
+        time_validity: TimeValidity,
+    ) -> Option<LimitOrder> {
+        if let Some(reason) = get_ticker_exclusion_reason(&ticker) {
+            println!("Ignoring stop limit request for {} - {}.", ticker, reason);
+            return None;
+        }
 
-                    match res {
-                        Ok(limit_orders) => {
-                            return Some(limit_orders);
-                        }
-                        Err(e) => {
-                            println!(
-                                "{}: Failed to deserialize into LimitOrder vector: {:?}",
-                                thread_name, e
-                            );
-                            return None;
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("Failed to deserialize JSON: {:?}", e);
-                    return None;
-                }
-            }
-        } else {
+        if is_protected_ticker(&ticker) {
+            println!("{} is protected. Ignoring stop limit request.", ticker);
+            return None;
+        }
+
+        // Same rounding as create_limit_order so the tick never works against us
+        let rounding_mode: RoundingMode = match quantity.is_buy() {
+            true => RoundingMode::Down,
+            false => RoundingMode::Up,
+        };
+        let (stop_price, quantity): (Price, Quantity) =
+            round_order(&ticker, &stop_price, &quantity, rounding_mode);
+        let (limit_price, _): (Price, Quantity) =
+            round_order(&ticker, &limit_price, &quantity, rounding_mode);
+
+        if quantity.is_zero() {
             println!(
-                "{}: Limit order failed. Code: {:?}",
-                thread_name,
-                &response.status()
+                "{} rounds to zero shares. Ignoring stop limit request.",
+                ticker
             );
             return None;
         }
+
+        let json_body: Value = json!({
+            "quantity": quantity,
+            "ticker": ticker,
+            "stopPrice": stop_price,
+            "limitPrice": limit_price,
+            "timeValidity": time_validity.as_str()
+        });
+
+        post_order("stop_limit", json_body).await
     }
 
     #[tokio::main]
     pub async fn get_single_order(id: u64) -> Option<MarketOrder> {
-        let auth_token: String = match *STOCK_VERSION {
-            VERSION::DEMO => env::var("DEMO_API_KEY").expect("DEMO_API_KEY must be set"),
-            VERSION::LIVE => env::var("LIVE_API_KEY").expect("LIVE_API_KEY must be set"),
-        };
+        let auth_token: String = get_profile().get_api_key();
 
-        let endpoint: &str = match *STOCK_VERSION {
-            VERSION::DEMO => &format!("https://demo.trading212.com/api/v0/equity/orders/{}", id),
-            VERSION::LIVE => &format!("https://live.trading212.com/api/v0/equity/orders/{}", id),
-        };
+        let endpoint: String = get_profile().get_api_url(&format!("equity/orders/{}", id));
 
         let client: Client = Client::new();
 
         let request: RequestBuilder = client
-            .get(endpoint)
+            .get(&endpoint)
             .header(header::AUTHORIZATION, auth_token);
 
         let resp: Result<Response, reqwest::Error> = request.send().await;
@@ -426,25 +533,30 @@ pub mod helpers {
 
     ////////////////////////////
 
+    // One page of order history. page_path is the nextPagePath of the page before, None for the newest
     #[tokio::main]
-    pub async fn get_all_historical_orders() -> Option<Vec<HistoricalOrder>> {
-        let auth_token: String = match *STOCK_VERSION {
-            VERSION::DEMO => env::var("DEMO_API_KEY").expect("DEMO_API_KEY must be set"),
-            VERSION::LIVE => env::var("LIVE_API_KEY").expect("LIVE_API_KEY must be set"),
-        };
+    pub async fn get_historical_orders_page(page_path: Option<&String>) -> Option<HistoryPage> {
+        let auth_token: String = get_profile().get_api_key();
 
-        let endpoint: &str = match *STOCK_VERSION {
-            VERSION::DEMO => "https://demo.trading212.com/api/v0/equity/history/orders?limit=50",
-            VERSION::LIVE => "https://live.trading212.com/api/v0/equity/history/orders?limit=50",
+        let endpoint: String = match page_path {
+            Some(page_path) => format!("{}{}", get_profile().get_base_url(), page_path),
+            None => get_profile().get_api_url("equity/history/orders?limit=50"),
         };
 
         let client: Client = Client::new();
 
         let request: RequestBuilder = client
-            .get(endpoint)
+            .get(&endpoint)
             .header(header::AUTHORIZATION, auth_token);
 
-        let response: Response = request.send().await.unwrap();
+        let resp: Result<Response, reqwest::Error> = request.send().await;
+
+        if resp.is_err() {
+            println!("Error fetching order history.\n");
+            return None;
+        }
+
+        let response: Response = resp.unwrap();
 
         if response.status().is_success() {
             let body: String = response.text().await.unwrap();
@@ -452,12 +564,18 @@ pub mod helpers {
 
             match result {
                 Ok(json) => {
-                    let res: Result<Vec<HistoricalOrder>, serde_json::Error> =
-                        serde_json::from_value(json);
+                    // A bare list is a single page with nothing after it
+                    let res: Result<HistoryPage, serde_json::Error> = match json.is_array() {
+                        true => from_value(json).map(|items| HistoryPage {
+                            items,
+                            next_page_path: None,
+                        }),
+                        false => from_value(json),
+                    };
 
                     match res {
-                        Ok(historical_orders) => {
-                            return Some(historical_orders);
+                        Ok(page) => {
+                            return Some(page);
                         }
                         Err(e) => {
                             println!(
@@ -487,20 +605,14 @@ pub mod helpers {
 
     #[tokio::main]
     pub async fn get_all_orders_request() -> Option<Vec<LimitOrder>> {
-        let auth_token: String = match *STOCK_VERSION {
-            VERSION::DEMO => env::var("DEMO_API_KEY").expect("DEMO_API_KEY must be set"),
-            VERSION::LIVE => env::var("LIVE_API_KEY").expect("LIVE_API_KEY must be set"),
-        };
+        let auth_token: String = get_profile().get_api_key();
 
-        let endpoint: &str = match *STOCK_VERSION {
-            VERSION::DEMO => "https://demo.trading212.com/api/v0/equity/orders",
-            VERSION::LIVE => "https://live.trading212.com/api/v0/equity/orders",
-        };
+        let endpoint: String = get_profile().get_api_url("equity/orders");
 
         let client: Client = Client::new();
 
         let request: RequestBuilder = client
-            .get(endpoint)
+            .get(&endpoint)
             .header(header::AUTHORIZATION, auth_token);
 
         let resp: Result<Response, reqwest::Error> = request.send().await;
@@ -551,19 +663,18 @@ pub mod helpers {
 
     #[tokio::main]
     pub async fn cancel_order(id: u64) -> Option<bool> {
-        let auth_token: String = match *STOCK_VERSION {
-            VERSION::DEMO => env::var("DEMO_API_KEY").expect("DEMO_API_KEY must be set"),
-            VERSION::LIVE => env::var("LIVE_API_KEY").expect("LIVE_API_KEY must be set"),
-        };
+        // Orders placed by hand are never touched
+        if !is_registered_order(id) {
+            println!(
+                "Order {} was not created by Apollo. Refusing to cancel.",
+                id
+            );
+            return None;
+        }
 
-        let endpoint: String = match *STOCK_VERSION {
-            VERSION::DEMO => {
-                format!("https://demo.trading212.com/api/v0/equity/orders/{}", id)
-            }
-            VERSION::LIVE => {
-                format!("https://live.trading212.com/api/v0/equity/orders/{}", id)
-            }
-        };
+        let auth_token: String = get_profile().get_api_key();
+
+        let endpoint: String = get_profile().get_api_url(&format!("equity/orders/{}", id));
 
         let client: Client = Client::new();
 
@@ -590,19 +701,21 @@ pub mod helpers {
 
     // ////////////////////////// GENERAL //////////////////////////////////
 
-    pub fn get_perc_increase(current_price: f64, perc_inc: f64) -> f64 {
-        let percentage_as_decimal: f64 = perc_inc / 100.0;
-        let increase: f64 = current_price * percentage_as_decimal;
-        current_price + increase
+    pub fn get_perc_increase(current_price: f64, perc_inc: f64) -> Price {
+        let percentage_as_decimal: BigDecimal = decimal_from_f64(perc_inc) / BigDecimal::from(100);
+        let price: BigDecimal = decimal_from_f64(current_price);
+        let increase: BigDecimal = &price * percentage_as_decimal;
+        Price(price + increase)
     }
 
-    pub fn get_perc_decrease(current_price: f64, perc_dec: f64) -> f64 {
-        let percentage_as_decimal = perc_dec / 100.0;
-        let decrease: f64 = current_price * percentage_as_decimal;
-        current_price - decrease
+    pub fn get_perc_decrease(current_price: f64, perc_dec: f64) -> Price {
+        let percentage_as_decimal: BigDecimal = decimal_from_f64(perc_dec) / BigDecimal::from(100);
+        let price: BigDecimal = decimal_from_f64(current_price);
+        let decrease: BigDecimal = &price * percentage_as_decimal;
+        Price(price - decrease)
     }
 
-    pub fn get_sell_quant(position: &Position) -> f64 {
+    pub fn get_sell_quant(position: &Position) -> Quantity {
         let final_quant: f64;
 
         if position.quantity > position.max_sell {
@@ -611,23 +724,48 @@ pub mod helpers {
             final_quant = position.quantity
         }
 
-        return final_quant;
+        return Quantity::from_f64(final_quant);
     }
 
-    pub fn get_buy_quantity(company: &FullCompanyInfo, balance_data: &BalanceObject) -> f64 {
-        // Calculating quantity to buy - rounded down to 2 decimal places
-        let amount_to_spend: f64 = calculate_amount_spent_per_ticker(balance_data);
-        let quant_to_buy: f64 = amount_to_spend / company.company_stock_quote.current_price;
-        let rounded_quant: f64 = round_down(quant_to_buy, 2);
-        let final_quant: f64;
+    pub fn get_buy_quantity(
+        company: &FullCompanyInfo,
+        balance_data: &BalanceObject,
+        fx_rates: &FxRates,
+    ) -> Option<Quantity> {
+        let currency: &str = &company.instrument.currency_code;
+
+        // Balance is held in account currency, so the quote has to be converted before sizing
+        let quote: Price = Price::from_f64(company.company_stock_quote.current_price);
+        let current_price: Price = match price_in_account_currency(&quote, currency, fx_rates) {
+            Some(current_price) => current_price,
+            None => {
+                println!("BT: No FX rate for {}. Unable to size order.", currency);
+                return None;
+            }
+        };
 
-        if rounded_quant > company.instrument.max_open_quantity {
-            final_quant = company.instrument.max_open_quantity
-        } else {
-            final_quant = rounded_quant
+        // Leave room for the FX fee charged on the buy
+        let amount_to_spend: Money = get_position_budget(company, balance_data)?;
+        let fee_rate: BigDecimal = get_fx_fee_rate(currency);
+        let amount_after_fees: Money = Money(amount_to_spend.0 / (BigDecimal::from(1) + fee_rate));
+
+        // Calculating quantity to buy - rounded down to the instrument's quantity precision
+        let quant_to_buy: Quantity = amount_after_fees.quantity_at(&current_price);
+        let rounded_quant: Quantity =
+            quant_to_buy.round_to_precision(quantity_precision(&company.instrument));
+
+        let final_quant: Quantity =
+            rounded_quant.min(Quantity::from_f64(company.instrument.max_open_quantity));
+
+        if final_quant < Quantity::from_f64(company.instrument.min_trade_quantity) {
+            println!(
+                "BT: {} shares of {} is below the minimum trade quantity of {}.",
+                final_quant, company.instrument.ticker, company.instrument.min_trade_quantity
+            );
+            return None;
         }
 
-        final_quant
+        Some(final_quant)
     }
 
     pub fn get_movement_direction(position: &Position) -> MOVEMENT_DIRECTION {
@@ -643,7 +781,9 @@ pub mod helpers {
             2,
         );
 
-        return percentage_change > *SELL_PERCENT_UP || percentage_change < *SELL_PERCENT_DOWN;
+        // calc_perc_change returns percent, the sell thresholds are fractions
+        return percentage_change > get_profile_strategy().sell_percent_up * 100.0
+            || percentage_change < get_profile_strategy().sell_percent_down * 100.0;
     }
 
     // pub fn create_sale_object(sell_order: &LimitOrder, position: &Position) -> SaleResult {
@@ -671,14 +811,7 @@ pub mod helpers {
     pub fn get_instruments() -> Vec<Instrument> {
         print_message(THREAD::COLLECTION, "Fetching new instruments data...");
 
-        let query: String = match *STOCK_VERSION {
-            VERSION::DEMO => {
-                String::from("https://demo.trading212.com/api/v0/equity/metadata/instruments")
-            }
-            VERSION::LIVE => {
-                String::from("https://live.trading212.com/api/v0/equity/metadata/instruments")
-            }
-        };
+        let query: String = get_profile().get_api_url("equity/metadata/instruments");
 
         let raw_data: Option<serde_json::Value> = make_request(query);
         let data: Value = raw_data.unwrap();
@@ -687,12 +820,32 @@ pub mod helpers {
         return from_value(data).unwrap();
     }
 
+    pub fn get_exchanges() -> Option<Vec<Exchange>> {
+        print_message(THREAD::COLLECTION, "Fetching exchange schedule data...");
+
+        let query: String = get_profile().get_api_url("equity/metadata/exchanges");
+
+        let raw_data: Option<serde_json::Value> = make_request(query);
+        if raw_data.is_none() {
+            print_message(THREAD::COLLECTION, "Exchange data was null");
+            return None;
+        }
+
+        match from_value(raw_data.unwrap()) {
+            Ok(exchanges) => {
+                print_message(THREAD::COLLECTION, "Fetch Successful.");
+                Some(exchanges)
+            }
+            Err(e) => {
+                println!("Failed to deserialise exchange data: {:?}", e);
+                None
+            }
+        }
+    }
+
     #[tokio::main]
     pub async fn make_request(endpoint: String) -> Option<Value> {
-        let auth_token: String = match *STOCK_VERSION {
-            VERSION::DEMO => env::var("DEMO_API_KEY").expect("DEMO_API_KEY must be set"),
-            VERSION::LIVE => env::var("LIVE_API_KEY").expect("LIVE_API_KEY must be set"),
-        };
+        let auth_token: String = get_profile().get_api_key();
 
         let client: Client = Client::new();
 
@@ -739,7 +892,11 @@ pub mod helpers {
         let all_orders_res: Vec<LimitOrder> = get_all_orders_request().unwrap();
         println!("\nCancelling current orders...\n");
 
-        for order in all_orders_res {
+        // Only Apollo's own orders - anything placed by hand is left alone
+        for order in all_orders_res
+            .into_iter()
+            .filter(|order| is_registered_order(order.id) && !is_protected_ticker(&order.ticker))
+        {
             println!("Cancelling Order: {:?} - {:?}", &order.id, &order.ticker);
             _ = cancel_order(order.id);
             sleep_thread(3);
@@ -753,11 +910,16 @@ pub mod helpers {
         }
 
         for position in raw_positions.unwrap() {
+            if is_protected_ticker(&position.ticker) {
+                println!("Skipping protected position {}.", position.ticker);
+                continue;
+            }
+
             println!(
                 "Selling {:?} shares of {:?}...",
                 position.quantity, position.ticker
             );
-            let sell_quantity: f64 = -position.quantity;
+            let sell_quantity: Quantity = Quantity::from_f64(position.quantity).negate();
             _ = create_market_order(position.ticker, sell_quantity);
             std::thread::sleep(std::time::Duration::from_secs(2))
         }
@@ -0,0 +1,84 @@
+pub mod ledger {
+    use std::collections::{HashMap, HashSet};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::control::profiles::profiles::get_profile_path;
+    use crate::helpers::helpers::helpers::{print_message, THREAD};
+    use crate::helpers::money::money::Quantity;
+    use crate::trading212::orders::orders::{OrderEvent, ORDER_STATE};
+
+    const LEDGER_FILE: &str = "ledger.json";
+
+    // What Apollo believes it holds, built from its own fills.
+    // Reconciliation compares this against the broker each cycle
+    #[derive(Debug, Serialize, Deserialize, Default)]
+    pub struct Ledger {
+        pub positions: HashMap<String, Quantity>, // Ticker -> shares held
+        pub ignored: HashSet<String>,             // Tickers Apollo has been told to leave alone
+    }
+
+    impl Ledger {
+        pub fn is_managed(&self, ticker: &String) -> bool {
+            self.positions.contains_key(ticker)
+        }
+
+        pub fn get_quantity(&self, ticker: &String) -> Quantity {
+            self.positions.get(ticker).cloned().unwrap_or_default()
+        }
+
+        pub fn set_quantity(&mut self, ticker: &String, quantity: Quantity) {
+            match quantity.is_buy() {
+                true => {
+                    self.positions.insert(ticker.clone(), quantity);
+                }
+                // Zero or less means the position is closed
+                false => {
+                    self.positions.remove(ticker);
+                }
+            }
+        }
+    }
+
+    pub fn load_ledger() -> Ledger {
+        match std::fs::read_to_string(get_profile_path(LEDGER_FILE)).ok() {
+            Some(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+            None => Ledger::default(),
+        }
+    }
+
+    pub fn save_ledger(ledger: &Ledger) {
+        let contents: String =
+            serde_json::to_string_pretty(ledger).expect("Ledger serialization failed");
+
+        if std::fs::write(get_profile_path(LEDGER_FILE), contents).is_err() {
+            print_message(THREAD::FILE, "Failed to write ledger");
+        }
+    }
+
+    // Applies the fills from terminal order events - buys add shares, sells remove them
+    pub fn record_fills(events: &Vec<OrderEvent>) {
+        let fills: Vec<&OrderEvent> = events
+            .iter()
+            .filter(|event| match event.state {
+                ORDER_STATE::FILLED | ORDER_STATE::CANCELLED => !event.filled_quantity.is_zero(),
+                _ => false,
+            })
+            .collect();
+
+        if fills.is_empty() {
+            return;
+        }
+
+        let mut ledger: Ledger = load_ledger();
+        for event in fills {
+            let held: Quantity = ledger.get_quantity(&event.ticker);
+            let updated: Quantity = match event.is_sell {
+                true => Quantity(held.0 - &event.filled_quantity.0),
+                false => Quantity(held.0 + &event.filled_quantity.0),
+            };
+            ledger.set_quantity(&event.ticker, updated);
+        }
+        save_ledger(&ledger);
+    }
+}
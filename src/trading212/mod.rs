@@ -1,2 +1,8 @@
+pub mod brackets;
+pub mod entry_pricer;
 pub mod helpers;
+pub mod ledger;
+pub mod orders;
+pub mod registry;
+pub mod schedules;
 pub mod types;
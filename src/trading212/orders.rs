@@ -0,0 +1,325 @@
+pub mod orders {
+    use std::collections::HashMap;
+
+    use chrono::Utc;
+    use serde::{Deserialize, Serialize};
+
+    use crate::file_control::write::write::log_order_events;
+    use crate::helpers::helpers::helpers::sleep_thread;
+    use crate::helpers::money::money::{Money, Price, Quantity};
+    use crate::trading212::entry_pricer::entry_pricer::record_entry_outcomes;
+    use crate::trading212::helpers::helpers::{get_historical_orders_page, get_single_order};
+    use crate::trading212::ledger::ledger::record_fills;
+    use crate::trading212::types::types::{HistoricalOrder, LimitOrder, MarketOrder};
+
+    // Furthest back an order is looked for in the history on each poll
+    const HISTORY_MAX_PAGES: usize = 5;
+    // Polls an order may be missing from both the active list and the history before it is given up
+    const MAX_HISTORY_MISSES: u32 = 10;
+
+    #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+    pub enum ORDER_STATE {
+        SUBMITTED,
+        PARTIALLY_FILLED,
+        FILLED,
+        CANCELLED,
+        REJECTED,
+    }
+
+    impl ORDER_STATE {
+        pub fn is_terminal(&self) -> bool {
+            match self {
+                ORDER_STATE::FILLED | ORDER_STATE::CANCELLED | ORDER_STATE::REJECTED => true,
+                ORDER_STATE::SUBMITTED | ORDER_STATE::PARTIALLY_FILLED => false,
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct TrackedOrder {
+        pub id: u64,
+        pub ticker: String,
+        pub quantity: Quantity, // Negative for sells
+        pub limit_price: Option<Price>,
+        pub filled_quantity: Quantity,
+        pub filled_value: Option<Money>,
+        pub state: ORDER_STATE,
+        pub submitted_at: i64,
+        pub updated_at: i64,
+        #[serde(default)]
+        pub is_market: bool,
+        #[serde(default)]
+        pub history_misses: u32, // Polls in a row the order could not be found anywhere
+    }
+
+    impl TrackedOrder {
+        pub fn is_sell(&self) -> bool {
+            !self.quantity.is_buy()
+        }
+
+        // Average price of everything filled so far
+        pub fn fill_price(&self) -> Option<Price> {
+            let filled_value: &Money = self.filled_value.as_ref()?;
+            if self.filled_quantity.is_zero() {
+                return None;
+            }
+            Some(Price(&filled_value.0 / self.filled_quantity.abs().0))
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct OrderEvent {
+        pub order_id: u64,
+        pub ticker: String,
+        pub is_sell: bool,
+        pub previous_state: ORDER_STATE,
+        pub state: ORDER_STATE,
+        pub filled_quantity: Quantity, // Total filled, always positive
+        pub fill_price: Option<Price>,
+        pub time: i64,
+    }
+
+    // Order history loaded so far, newest first. Pages are fetched only as far back as a lookup
+    // needs, and once each, however many orders are looked up
+    #[derive(Default)]
+    pub struct HistoryCursor {
+        orders: Vec<HistoricalOrder>,
+        next_page_path: Option<String>,
+        pages: usize,
+        exhausted: bool,
+    }
+
+    impl HistoryCursor {
+        pub fn find(&mut self, id: u64) -> Option<HistoricalOrder> {
+            loop {
+                if let Some(historical) = self
+                    .orders
+                    .iter()
+                    .find(|historical| historical.id == id || historical.parent_order == id)
+                {
+                    return Some(historical.clone());
+                }
+
+                if self.exhausted || self.pages >= HISTORY_MAX_PAGES {
+                    return None;
+                }
+
+                match get_historical_orders_page(self.next_page_path.as_ref()) {
+                    Some(page) => {
+                        self.pages += 1;
+                        self.orders.extend(page.items);
+                        self.exhausted = page.next_page_path.is_none();
+                        self.next_page_path = page.next_page_path;
+                    }
+                    None => {
+                        self.exhausted = true;
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+
+    // Trading212 status strings - https://t212public-api-docs.redoc.ly/#operation/orders
+    pub fn map_order_status(status: &str, filled_quantity: &Quantity) -> ORDER_STATE {
+        match status.to_ascii_uppercase().as_str() {
+            "FILLED" => ORDER_STATE::FILLED,
+            "PARTIALLY_FILLED" => ORDER_STATE::PARTIALLY_FILLED,
+            "REJECTED" => ORDER_STATE::REJECTED,
+            // A cancelled order may still have filled in part - the event carries the filled quantity
+            "CANCELLED" | "REPLACED" => ORDER_STATE::CANCELLED,
+            _ => match filled_quantity.is_zero() {
+                true => ORDER_STATE::SUBMITTED,
+                false => ORDER_STATE::PARTIALLY_FILLED,
+            },
+        }
+    }
+
+    // Tracks every order Apollo places until it reaches a terminal state
+    #[derive(Debug, Serialize, Deserialize, Default)]
+    pub struct OrderManager {
+        pub orders: HashMap<u64, TrackedOrder>,
+    }
+
+    impl OrderManager {
+        fn track(
+            &mut self,
+            id: u64,
+            ticker: &String,
+            quantity: &Quantity,
+            limit_price: &Option<Price>,
+            is_market: bool,
+        ) {
+            let now: i64 = Utc::now().timestamp();
+            self.orders.insert(
+                id,
+                TrackedOrder {
+                    id,
+                    ticker: ticker.clone(),
+                    quantity: quantity.clone(),
+                    limit_price: limit_price.clone(),
+                    filled_quantity: Quantity::default(),
+                    filled_value: None,
+                    state: ORDER_STATE::SUBMITTED,
+                    submitted_at: now,
+                    updated_at: now,
+                    is_market,
+                    history_misses: 0,
+                },
+            );
+        }
+
+        pub fn track_limit_order(&mut self, order: &LimitOrder) {
+            self.track(
+                order.id,
+                &order.ticker,
+                &order.quantity,
+                &order.limit_price,
+                false,
+            );
+        }
+
+        pub fn track_market_order(&mut self, order: &MarketOrder) {
+            self.track(order.id, &order.ticker, &order.quantity, &None, true);
+        }
+
+        pub fn get_open_orders(&self) -> Vec<&TrackedOrder> {
+            self.orders
+                .values()
+                .filter(|order| !order.state.is_terminal())
+                .collect()
+        }
+
+        pub fn has_open_order(&self, ticker: &String) -> bool {
+            self.get_open_orders()
+                .iter()
+                .any(|order| order.ticker.eq_ignore_ascii_case(ticker))
+        }
+
+        // A market order still open means a sell or exit is already on its way
+        pub fn has_open_market_order(&self, ticker: &String) -> bool {
+            self.get_open_orders()
+                .iter()
+                .any(|order| order.is_market && order.ticker.eq_ignore_ascii_case(ticker))
+        }
+
+        // Applies the latest status and returns an event if anything changed
+        fn apply(
+            order: &mut TrackedOrder,
+            state: ORDER_STATE,
+            filled_quantity: Quantity,
+            filled_value: Option<Money>,
+        ) -> Option<OrderEvent> {
+            let filled_quantity: Quantity = filled_quantity.abs();
+            if state == order.state && filled_quantity == order.filled_quantity {
+                return None;
+            }
+
+            let previous_state: ORDER_STATE = order.state;
+            order.state = state;
+            order.filled_quantity = filled_quantity;
+            if filled_value.is_some() {
+                order.filled_value = filled_value;
+            }
+            order.updated_at = Utc::now().timestamp();
+
+            Some(OrderEvent {
+                order_id: order.id,
+                ticker: order.ticker.clone(),
+                is_sell: order.is_sell(),
+                previous_state,
+                state,
+                filled_quantity: order.filled_quantity.clone(),
+                fill_price: order.fill_price(),
+                time: order.updated_at,
+            })
+        }
+
+        // Polls every open order. Orders that have left the active list are looked up in the history
+        pub fn poll(&mut self) -> Vec<OrderEvent> {
+            let open_ids: Vec<u64> = self
+                .get_open_orders()
+                .iter()
+                .map(|order| order.id)
+                .collect();
+
+            if open_ids.is_empty() {
+                return Vec::new();
+            }
+
+            let mut events: Vec<OrderEvent> = Vec::new();
+            let mut history: HistoryCursor = HistoryCursor::default();
+
+            for id in open_ids {
+                let order: &mut TrackedOrder = self.orders.get_mut(&id).unwrap();
+
+                let event: Option<OrderEvent> = match get_single_order(id) {
+                    Some(active) => {
+                        order.history_misses = 0;
+                        let state: ORDER_STATE =
+                            map_order_status(&active.status, &active.filled_quantity);
+                        Self::apply(order, state, active.filled_quantity, active.filled_value)
+                    }
+                    None => {
+                        match history.find(id) {
+                            Some(historical) => {
+                                let filled_quantity: Quantity =
+                                    Quantity::from_f64(historical.filled_quantity);
+                                let state: ORDER_STATE =
+                                    map_order_status(&historical.status, &filled_quantity);
+                                let filled_value: Option<Money> =
+                                    historical.filled_value.map(Money::from_f64).or(Some(Money(
+                                        Price::from_f64(historical.fill_price).0
+                                            * filled_quantity.abs().0,
+                                    )));
+                                Self::apply(order, state, filled_quantity, filled_value)
+                            }
+                            None => {
+                                order.history_misses += 1;
+                                match order.history_misses >= MAX_HISTORY_MISSES {
+                                    // Too far back in the history to find. Whatever filled so
+                                    // far is kept, and the order stops blocking its ticker
+                                    true => {
+                                        println!(
+                                            "Order {} ({}) not found after {} polls. Treating it as cancelled.",
+                                            id, order.ticker, order.history_misses
+                                        );
+                                        let filled_quantity: Quantity =
+                                            order.filled_quantity.clone();
+                                        Self::apply(
+                                            order,
+                                            ORDER_STATE::CANCELLED,
+                                            filled_quantity,
+                                            None,
+                                        )
+                                    }
+                                    false => None,
+                                }
+                            }
+                        }
+                    }
+                };
+
+                if let Some(event) = event {
+                    println!(
+                        "Order {} ({}) {:?} -> {:?}",
+                        event.order_id, event.ticker, event.previous_state, event.state
+                    );
+                    events.push(event);
+                }
+                sleep_thread(1);
+            }
+
+            if !events.is_empty() {
+                log_order_events(&events);
+                record_fills(&events);
+                record_entry_outcomes(&events);
+            }
+
+            // Terminal orders only need to be kept until their final event has gone out
+            self.orders.retain(|_, order| !order.state.is_terminal());
+
+            events
+        }
+    }
+}
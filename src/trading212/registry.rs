@@ -0,0 +1,87 @@
+pub mod registry {
+    use std::collections::{HashMap, HashSet};
+    use std::sync::Mutex;
+
+    use chrono::Utc;
+    use lazy_static::lazy_static;
+    use serde::{Deserialize, Serialize};
+
+    use crate::control::profiles::profiles::get_profile_path;
+    use crate::helpers::helpers::helpers::{print_message, THREAD};
+    use crate::PROTECTED_TICKERS;
+
+    const REGISTRY_FILE: &str = "order_registry.json";
+
+    lazy_static! {
+        // Every thread creates orders, so file access goes through one lock
+        static ref REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct RegisteredOrder {
+        pub ticker: String,
+        pub created_at: i64,
+    }
+
+    // Ids of every order Apollo has placed. Anything else on the account belongs to someone else
+    #[derive(Debug, Serialize, Deserialize, Default)]
+    pub struct OrderRegistry {
+        pub orders: HashMap<u64, RegisteredOrder>,
+    }
+
+    fn load_registry() -> OrderRegistry {
+        match std::fs::read_to_string(get_profile_path(REGISTRY_FILE)).ok() {
+            Some(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+            None => OrderRegistry::default(),
+        }
+    }
+
+    fn save_registry(registry: &OrderRegistry) {
+        let contents: String =
+            serde_json::to_string_pretty(registry).expect("Order registry serialization failed");
+
+        if std::fs::write(get_profile_path(REGISTRY_FILE), contents).is_err() {
+            print_message(THREAD::FILE, "Failed to write order registry");
+        }
+    }
+
+    pub fn register_order(id: u64, ticker: &String) {
+        let _guard = REGISTRY_LOCK.lock().unwrap();
+        let mut registry: OrderRegistry = load_registry();
+        registry.orders.insert(
+            id,
+            RegisteredOrder {
+                ticker: ticker.clone(),
+                created_at: Utc::now().timestamp(),
+            },
+        );
+        save_registry(&registry);
+    }
+
+    // Drops every order not in open_ids, other than ones registered after the ids were listed.
+    // Age says nothing - a GTC bracket stop can sit for months
+    pub fn prune_registry(open_ids: &HashSet<u64>, listed_at: i64) {
+        let _guard = REGISTRY_LOCK.lock().unwrap();
+        let mut registry: OrderRegistry = load_registry();
+        let before: usize = registry.orders.len();
+
+        registry
+            .orders
+            .retain(|id, order| open_ids.contains(id) || order.created_at >= listed_at);
+        if registry.orders.len() != before {
+            save_registry(&registry);
+        }
+    }
+
+    pub fn is_registered_order(id: u64) -> bool {
+        let _guard = REGISTRY_LOCK.lock().unwrap();
+        load_registry().orders.contains_key(&id)
+    }
+
+    // Protected tickers are never bought, sold or cancelled by Apollo
+    pub fn is_protected_ticker(ticker: &String) -> bool {
+        PROTECTED_TICKERS
+            .iter()
+            .any(|protected| protected.eq_ignore_ascii_case(ticker))
+    }
+}
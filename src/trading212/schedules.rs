@@ -0,0 +1,127 @@
+pub mod schedules {
+    use std::collections::HashMap;
+
+    use chrono::{DateTime, Utc};
+
+    use crate::trading212::types::types::{Exchange, Instrument, TimeEvent, WorkingSchedule};
+    use crate::TRADE_EXTENDED_HOURS;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum MARKET_SESSION {
+        PRE_MARKET,
+        REGULAR,
+        BREAK,
+        AFTER_HOURS,
+        OVERNIGHT,
+        CLOSED,
+    }
+
+    // Working schedule id -> schedule, built once per exchange refresh
+    pub struct ScheduleCalendar {
+        schedules: HashMap<i64, WorkingSchedule>,
+        exchange_names: HashMap<i64, String>,
+    }
+
+    fn session_after_event(event_type: &str) -> MARKET_SESSION {
+        match event_type {
+            "OPEN" | "BREAK_END" => MARKET_SESSION::REGULAR,
+            "BREAK_START" => MARKET_SESSION::BREAK,
+            "PRE_MARKET_OPEN" => MARKET_SESSION::PRE_MARKET,
+            "AFTER_HOURS_OPEN" => MARKET_SESSION::AFTER_HOURS,
+            "OVERNIGHT_OPEN" => MARKET_SESSION::OVERNIGHT,
+            _ => MARKET_SESSION::CLOSED, // CLOSE, AFTER_HOURS_CLOSE and anything unknown
+        }
+    }
+
+    fn parse_event_time(event: &TimeEvent) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&event.date)
+            .ok()
+            .map(|date| date.with_timezone(&Utc))
+    }
+
+    pub fn get_session_at(schedule: &WorkingSchedule, time: DateTime<Utc>) -> MARKET_SESSION {
+        // The session in effect is set by the latest event at or before the given time
+        let mut latest: Option<(DateTime<Utc>, &TimeEvent)> = None;
+
+        for event in &schedule.time_events {
+            let event_time: DateTime<Utc> = match parse_event_time(event) {
+                Some(event_time) => event_time,
+                None => continue,
+            };
+
+            if event_time > time {
+                continue;
+            }
+
+            // Events sharing a timestamp (e.g. CLOSE and AFTER_HOURS_OPEN) resolve to the later listed one
+            if latest.map_or(true, |(latest_time, _)| event_time >= latest_time) {
+                latest = Some((event_time, event));
+            }
+        }
+
+        match latest {
+            Some((_, event)) => session_after_event(&event.event_type),
+            None => MARKET_SESSION::CLOSED,
+        }
+    }
+
+    pub fn is_session_tradable(session: MARKET_SESSION) -> bool {
+        match session {
+            MARKET_SESSION::REGULAR => true,
+            MARKET_SESSION::PRE_MARKET
+            | MARKET_SESSION::AFTER_HOURS
+            | MARKET_SESSION::OVERNIGHT => *TRADE_EXTENDED_HOURS,
+            MARKET_SESSION::BREAK | MARKET_SESSION::CLOSED => false,
+        }
+    }
+
+    impl ScheduleCalendar {
+        pub fn new(exchanges: &Vec<Exchange>) -> ScheduleCalendar {
+            let mut schedules: HashMap<i64, WorkingSchedule> = HashMap::new();
+            let mut exchange_names: HashMap<i64, String> = HashMap::new();
+
+            for exchange in exchanges {
+                for schedule in &exchange.working_schedules {
+                    schedules.insert(schedule.id, schedule.clone());
+                    exchange_names.insert(schedule.id, exchange.name.clone());
+                }
+            }
+
+            ScheduleCalendar {
+                schedules,
+                exchange_names,
+            }
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.schedules.is_empty()
+        }
+
+        pub fn get_exchange_name(&self, instrument: &Instrument) -> Option<&String> {
+            self.exchange_names.get(&instrument.working_schedule_id)
+        }
+
+        pub fn get_instrument_session(
+            &self,
+            instrument: &Instrument,
+            time: DateTime<Utc>,
+        ) -> MARKET_SESSION {
+            match self.schedules.get(&instrument.working_schedule_id) {
+                Some(schedule) => get_session_at(schedule, time),
+                None => MARKET_SESSION::CLOSED,
+            }
+        }
+
+        pub fn is_instrument_tradable_now(&self, instrument: &Instrument) -> bool {
+            is_session_tradable(self.get_instrument_session(instrument, Utc::now()))
+        }
+
+        // True while any venue Apollo knows about is in a tradable session
+        pub fn is_any_market_open(&self) -> bool {
+            let now: DateTime<Utc> = Utc::now();
+            self.schedules
+                .values()
+                .any(|schedule| is_session_tradable(get_session_at(schedule, now)))
+        }
+    }
+}
@@ -5,15 +5,20 @@ pub mod types {
     use serde::{Deserialize, Deserializer, Serialize};
     use serde_json::Value;
 
-    #[derive(Debug)]
+    use crate::helpers::money::money::{Money, Price, Quantity};
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct SystemLimitOrder {
         pub movement_direction: MOVEMENT_DIRECTION,
         pub creation_time: SystemTime,
         pub limit_order: LimitOrder,
         pub sell_attempts: i32,
+        pub sold_quantity: Quantity, // Filled across every order in the ladder so far
+        #[serde(default)]
+        pub average_price: f64, // Of the position when the ladder opened - the sale is logged after it has gone
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub enum MOVEMENT_DIRECTION {
         UP,
         DOWN,
@@ -73,31 +78,53 @@ pub mod types {
         pub working_schedule_id: i64,
     }
 
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct Exchange {
+        pub id: i64,
+        pub name: String,
+        #[serde(rename = "workingSchedules")]
+        pub working_schedules: Vec<WorkingSchedule>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct WorkingSchedule {
+        pub id: i64,
+        #[serde(rename = "timeEvents")]
+        pub time_events: Vec<TimeEvent>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, Clone)]
+    pub struct TimeEvent {
+        pub date: String, // RFC3339 timestamp of the event
+        #[serde(rename = "type")]
+        pub event_type: String, // OPEN, CLOSE, BREAK_START, BREAK_END, PRE_MARKET_OPEN, AFTER_HOURS_OPEN, AFTER_HOURS_CLOSE, OVERNIGHT_OPEN
+    }
+
     #[derive(Debug, Serialize, Deserialize)]
     pub struct MarketOrder {
         #[serde(rename = "creationTime")]
         pub creation_time: String,
 
         #[serde(rename = "filledQuantity")]
-        pub filled_quantity: f64,
+        pub filled_quantity: Quantity,
 
         #[serde(rename = "filledValue")]
-        pub filled_value: Option<f64>,
+        pub filled_value: Option<Money>,
 
         #[serde(rename = "id")]
         pub id: u64,
 
         #[serde(rename = "limitPrice")]
-        pub limit_price: Option<f64>,
+        pub limit_price: Option<Price>,
 
         #[serde(rename = "quantity")]
-        pub quantity: f64,
+        pub quantity: Quantity,
 
         #[serde(rename = "status")]
         pub status: String,
 
         #[serde(rename = "stopPrice")]
-        pub stop_price: Option<f64>,
+        pub stop_price: Option<Price>,
 
         #[serde(rename = "strategy")]
         pub strategy: String,
@@ -109,34 +136,34 @@ pub mod types {
         pub order_type: String,
 
         #[serde(rename = "value")]
-        pub value: Option<f64>,
+        pub value: Option<Money>,
     }
 
-    #[derive(Debug, Serialize, Deserialize)]
+    #[derive(Debug, Serialize, Deserialize, Clone)]
     pub struct LimitOrder {
         #[serde(rename = "creationTime")]
         pub creation_time: String,
 
         #[serde(rename = "filledQuantity")]
-        pub filled_quantity: f64,
+        pub filled_quantity: Quantity,
 
         #[serde(rename = "filledValue")]
-        pub filled_value: Option<f64>,
+        pub filled_value: Option<Money>,
 
         #[serde(rename = "id")]
         pub id: u64,
 
         #[serde(rename = "limitPrice")]
-        pub limit_price: Option<f64>,
+        pub limit_price: Option<Price>,
 
         #[serde(rename = "quantity")]
-        pub quantity: f64,
+        pub quantity: Quantity,
 
         #[serde(rename = "status")]
         pub status: String,
 
         #[serde(rename = "stopPrice")]
-        pub stop_price: Option<f64>,
+        pub stop_price: Option<Price>,
 
         #[serde(rename = "strategy")]
         pub strategy: String,
@@ -148,7 +175,7 @@ pub mod types {
         pub order_type: String,
 
         #[serde(rename = "value")]
-        pub value: Option<f64>,
+        pub value: Option<Money>,
     }
 
     fn deserialize_datetime_to_string<'de, D>(deserializer: D) -> Result<String, D::Error>
@@ -206,6 +233,14 @@ pub mod types {
         pub status: String,
     }
 
+    // One page of equity/history/orders, newest first
+    #[derive(Clone, Debug, Serialize, Deserialize, Default)]
+    pub struct HistoryPage {
+        pub items: Vec<HistoricalOrder>,
+        #[serde(rename = "nextPagePath")]
+        pub next_page_path: Option<String>, // None on the last page
+    }
+
     #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct HistoricalTaxItem {
         pub fill_id: String,